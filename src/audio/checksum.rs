@@ -0,0 +1,90 @@
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::utils::{Crc32, make_element};
+use gstreamer::{ClockTime, MessageView, Pipeline, State, prelude::*};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+const POLL_INTERVAL: ClockTime = ClockTime::from_mseconds(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioChecksum {
+    pub crc32: u32,
+    pub duration_ms: u64,
+}
+
+impl AudioChecksum {
+    pub fn compute(path: &Path, cancel: &CancellationToken) -> Result<Self> {
+        let pipeline = Pipeline::new();
+
+        let filesrc = make_element("filesrc")?;
+        filesrc.set_property_from_str("location", &path.to_string_lossy());
+
+        let decodebin = make_element("decodebin")?;
+        let sink = make_element("fakesink")?;
+        sink.set_property("sync", false);
+        sink.set_property("signal-handoffs", true);
+
+        pipeline.add_many([&filesrc, &decodebin, &sink])?;
+        filesrc.link(&decodebin)?;
+
+        let sink_clone = sink.clone();
+        decodebin.connect_pad_added(move |_element, src_pad| {
+            let Some(sink_pad) = sink_clone.static_pad("sink") else {
+                return;
+            };
+            if sink_pad.is_linked() {
+                return;
+            }
+            let _ = src_pad.link(&sink_pad);
+        });
+
+        let accumulator = Arc::new(Mutex::new((Crc32::new(), 0u64)));
+        let accumulator_clone = accumulator.clone();
+        sink.connect("handoff", false, move |values| {
+            if let Ok(buffer) = values[1].get::<gstreamer::Buffer>()
+                && let (Ok(map), Ok(mut acc)) = (buffer.map_readable(), accumulator_clone.lock())
+            {
+                acc.0.update(map.as_slice());
+                acc.1 += buffer.duration().map(|d| d.nseconds()).unwrap_or(0);
+            }
+            None
+        });
+
+        let bus = pipeline.bus()
+            .ok_or_else(|| Error::Pipeline("Pipeline sans bus".to_string()))?;
+
+        if let Err(e) = pipeline.set_state(State::Playing) {
+            let _ = pipeline.set_state(State::Null);
+            return Err(e.into());
+        }
+
+        let result = loop {
+            if cancel.is_cancelled() {
+                break Err(Error::Cancelled);
+            }
+            let Some(msg) = bus.timed_pop(POLL_INTERVAL) else {
+                continue;
+            };
+            match msg.view() {
+                MessageView::Eos(_) => break Ok(()),
+                MessageView::Error(err) => {
+                    break Err(Error::Pipeline(format!("{} : {}", path.display(), err.error())));
+                }
+                _ => {}
+            }
+        };
+
+        pipeline.set_state(State::Null)?;
+        result?;
+
+        let acc = accumulator
+            .lock()
+            .map_err(|_| Error::Pipeline("Somme de contrôle indisponible".to_string()))?;
+
+        Ok(Self {
+            crc32: acc.0.finish(),
+            duration_ms: acc.1 / 1_000_000,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use super::{CustomProfile, EncoderProperty};
 use gstreamer_pbutils::{EncodingAudioProfile, EncodingContainerProfile};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,6 +9,12 @@ pub enum AudioFormat {
     Mp3,
     Aac,
     Wavpack,
+    Alac,
+    Wav,
+    Aiff,
+    OpusMatroska,
+    FlacMatroska,
+    Custom(usize),
 }
 
 impl AudioFormat {
@@ -19,6 +26,42 @@ impl AudioFormat {
             AudioFormat::Mp3 => "mp3",
             AudioFormat::Aac => "m4a",
             AudioFormat::Wavpack => "wv",
+            AudioFormat::Alac => "m4a",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Aiff => "aiff",
+            AudioFormat::OpusMatroska | AudioFormat::FlacMatroska => "mka",
+            AudioFormat::Custom(_) => self.custom().map_or("audio", |profile| profile.extension.as_str()),
+        }
+    }
+
+    pub fn codec_name(&self) -> &str {
+        match self {
+            AudioFormat::Opus | AudioFormat::OpusMatroska => "Opus",
+            AudioFormat::Vorbis => "Vorbis",
+            AudioFormat::Flac | AudioFormat::FlacMatroska => "FLAC",
+            AudioFormat::Mp3 => "MP3",
+            AudioFormat::Aac => "AAC",
+            AudioFormat::Wavpack => "WavPack",
+            AudioFormat::Alac => "ALAC",
+            AudioFormat::Wav | AudioFormat::Aiff => "PCM",
+            AudioFormat::Custom(_) => self.custom().map_or("", |profile| profile.codec.as_str()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::Vorbis => "vorbis",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Aac => "aac",
+            AudioFormat::Wavpack => "wavpack",
+            AudioFormat::Alac => "alac",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Aiff => "aiff",
+            AudioFormat::OpusMatroska => "opus-mka",
+            AudioFormat::FlacMatroska => "flac-mka",
+            AudioFormat::Custom(_) => self.custom().map_or("", |profile| profile.id.as_str()),
         }
     }
 
@@ -30,11 +73,49 @@ impl AudioFormat {
             AudioFormat::Mp3 => "MP3",
             AudioFormat::Aac => "AAC",
             AudioFormat::Wavpack => "WavPack",
+            AudioFormat::Alac => "ALAC",
+            AudioFormat::Wav => "WAV",
+            AudioFormat::Aiff => "AIFF",
+            AudioFormat::OpusMatroska => "Opus (Matroska)",
+            AudioFormat::FlacMatroska => "FLAC (Matroska)",
+            AudioFormat::Custom(_) => self.custom().map_or("", |profile| profile.name.as_str()),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<AudioFormat> {
+        let value = value.to_lowercase();
+        let formats = Self::all_formats();
+        formats
+            .iter()
+            .find(|format| format.id() == value || format.name().to_lowercase() == value)
+            .or_else(|| formats.iter().find(|format| format.file_extension() == value))
+            .copied()
+    }
+
+    pub fn custom(&self) -> Option<&'static CustomProfile> {
+        match self {
+            AudioFormat::Custom(index) => CustomProfile::registered().get(*index),
+            _ => None,
         }
     }
 
+    pub fn encoder_properties(&self) -> &[EncoderProperty] {
+        self.custom().map_or(&[], |profile| profile.properties.as_slice())
+    }
+
     pub fn is_lossless(&self) -> bool {
-        matches!(self, AudioFormat::Flac | AudioFormat::Wavpack)
+        if let Some(profile) = self.custom() {
+            return profile.lossless;
+        }
+        matches!(
+            self,
+            AudioFormat::Flac
+                | AudioFormat::Wavpack
+                | AudioFormat::Alac
+                | AudioFormat::Wav
+                | AudioFormat::Aiff
+                | AudioFormat::FlacMatroska
+        )
     }
 
     pub fn create_encoding_profile(&self) -> EncodingContainerProfile {
@@ -100,17 +181,78 @@ impl AudioFormat {
                     .add_profile(audio_profile)
                     .build()
             }
+            AudioFormat::Alac => {
+                let alac_caps = gstreamer::Caps::builder("audio/x-alac").build();
+                let audio_profile = EncodingAudioProfile::builder(&alac_caps).build();
+                
+                let mp4_caps = gstreamer::Caps::builder("video/quicktime")
+                    .field("variant", "iso")
+                    .build();
+                EncodingContainerProfile::builder(&mp4_caps)
+                    .add_profile(audio_profile)
+                    .build()
+            }
+            AudioFormat::Wav => {
+                let raw_caps = gstreamer::Caps::builder("audio/x-raw").build();
+                let audio_profile = EncodingAudioProfile::builder(&raw_caps).build();
+                
+                let wav_caps = gstreamer::Caps::builder("audio/x-wav").build();
+                EncodingContainerProfile::builder(&wav_caps)
+                    .add_profile(audio_profile)
+                    .build()
+            }
+            AudioFormat::Aiff => {
+                let raw_caps = gstreamer::Caps::builder("audio/x-raw").build();
+                let audio_profile = EncodingAudioProfile::builder(&raw_caps).build();
+                
+                let aiff_caps = gstreamer::Caps::builder("audio/x-aiff").build();
+                EncodingContainerProfile::builder(&aiff_caps)
+                    .add_profile(audio_profile)
+                    .build()
+            }
+            AudioFormat::OpusMatroska => {
+                let opus_caps = gstreamer::Caps::builder("audio/x-opus").build();
+                let audio_profile = EncodingAudioProfile::builder(&opus_caps).build();
+                
+                let mka_caps = gstreamer::Caps::builder("audio/x-matroska").build();
+                EncodingContainerProfile::builder(&mka_caps)
+                    .add_profile(audio_profile)
+                    .build()
+            }
+            AudioFormat::FlacMatroska => {
+                let flac_caps = gstreamer::Caps::builder("audio/x-flac").build();
+                let audio_profile = EncodingAudioProfile::builder(&flac_caps).build();
+                
+                let mka_caps = gstreamer::Caps::builder("audio/x-matroska").build();
+                EncodingContainerProfile::builder(&mka_caps)
+                    .add_profile(audio_profile)
+                    .build()
+            }
+            AudioFormat::Custom(_) => match self.custom() {
+                Some(profile) => profile.create_encoding_profile(),
+                None => {
+                    let any_caps = gstreamer::Caps::new_any();
+                    EncodingContainerProfile::builder(&any_caps).build()
+                }
+            },
         }
     }
 
     pub fn all_formats() -> Vec<AudioFormat> {
-        vec![
+        let mut formats = vec![
             AudioFormat::Opus,
             AudioFormat::Vorbis,
             AudioFormat::Flac,
             AudioFormat::Mp3,
             AudioFormat::Aac,
             AudioFormat::Wavpack,
-        ]
+            AudioFormat::Alac,
+            AudioFormat::Wav,
+            AudioFormat::Aiff,
+            AudioFormat::OpusMatroska,
+            AudioFormat::FlacMatroska,
+        ];
+        formats.extend((0..CustomProfile::registered().len()).map(AudioFormat::Custom));
+        formats
     }
 }
@@ -0,0 +1,114 @@
+use crate::error::{Error, Result};
+use crate::utils::format_time;
+use gio::prelude::*;
+use gstreamer::ClockTime;
+use gstreamer_pbutils::prelude::*;
+use gstreamer_pbutils::{Discoverer, DiscovererContainerInfo, pb_utils_get_codec_description};
+
+const DISCOVERY_TIMEOUT: ClockTime = ClockTime::from_seconds(10);
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaInfo {
+    pub container: Option<String>,
+    pub codec: Option<String>,
+    pub bitrate: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub depth: Option<u32>,
+    pub channels: Option<u32>,
+    pub duration: Option<ClockTime>,
+}
+
+impl MediaInfo {
+    pub fn discover(uri: &str) -> Result<Self> {
+        let discoverer = Discoverer::new(DISCOVERY_TIMEOUT)?;
+        let info = discoverer.discover_uri(uri)?;
+
+        let streams = info.audio_streams();
+        let audio = streams
+            .first()
+            .ok_or_else(|| Error::Pipeline(format!("{} : aucun flux audio", uri)))?;
+
+        let non_zero = |value: u32| (value > 0).then_some(value);
+        let tags = info.tags();
+        let tag_bitrate = tags.as_ref().and_then(|tags| {
+            tags.get::<gstreamer::tags::Bitrate>()
+                .or_else(|| tags.get::<gstreamer::tags::NominalBitrate>())
+                .map(|bitrate| bitrate.get())
+        });
+
+        let duration = info.duration();
+        let file_bitrate = gio::File::for_uri(uri)
+            .path()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .zip(duration.filter(|d| d.mseconds() > 0))
+            .map(|(metadata, duration)| (metadata.len() * 8 * 1000 / duration.mseconds()) as u32);
+
+        let container = info
+            .stream_info()
+            .filter(|stream| stream.is::<DiscovererContainerInfo>())
+            .and_then(|stream| stream.caps())
+            .map(|caps| pb_utils_get_codec_description(&caps).to_string());
+
+        Ok(Self {
+            container,
+            codec: audio.caps().map(|caps| pb_utils_get_codec_description(&caps).to_string()),
+            bitrate: non_zero(audio.bitrate()).or(tag_bitrate).or(file_bitrate),
+            sample_rate: non_zero(audio.sample_rate()),
+            depth: non_zero(audio.depth()),
+            channels: non_zero(audio.channels()),
+            duration,
+        })
+    }
+
+    pub fn channel_layout(&self) -> Option<String> {
+        self.channels.map(|channels| match channels {
+            1 => "mono".to_string(),
+            2 => "stéréo".to_string(),
+            n => format!("{} canaux", n),
+        })
+    }
+
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref codec) = self.codec {
+            parts.push(codec.clone());
+        }
+        if let Some(bitrate) = self.bitrate {
+            parts.push(format!("{} kb/s", bitrate / 1000));
+        }
+        if let Some(sample_rate) = self.sample_rate {
+            parts.push(format!("{:.1} kHz", sample_rate as f64 / 1000.0));
+        }
+        if let Some(depth) = self.depth {
+            parts.push(format!("{} bits", depth));
+        }
+        if let Some(layout) = self.channel_layout() {
+            parts.push(layout);
+        }
+        parts.join(" | ")
+    }
+
+    pub fn print(&self) {
+        if let Some(ref codec) = self.codec {
+            println!("   Codec : {}", codec);
+        }
+        if let Some(ref container) = self.container {
+            println!("   Conteneur : {}", container);
+        }
+        if let Some(bitrate) = self.bitrate {
+            println!("   Débit : {} kb/s", bitrate / 1000);
+        }
+        if let Some(sample_rate) = self.sample_rate {
+            println!("   Fréquence : {} Hz", sample_rate);
+        }
+        if let Some(depth) = self.depth {
+            println!("   Résolution : {} bits", depth);
+        }
+        if let Some(layout) = self.channel_layout() {
+            println!("   Canaux : {}", layout);
+        }
+        if let Some(duration) = self.duration {
+            println!("   Durée : {}", format_time(duration.seconds()));
+        }
+    }
+}
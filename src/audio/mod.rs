@@ -1,5 +1,15 @@
+pub mod checksum;
 pub mod format;
+pub mod info;
+pub mod profile;
+pub mod replaygain;
 pub mod validator;
+pub mod verify;
 
+pub use checksum::AudioChecksum;
 pub use format::AudioFormat;
+pub use info::MediaInfo;
+pub use profile::{CustomProfile, EncoderProperty};
+pub use replaygain::{ReplayGainAnalyzer, ReplayGainTagger};
 pub use validator::FormatValidator;
+pub use verify::IntegrityChecker;
@@ -0,0 +1,260 @@
+use super::FormatValidator;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use glib::{KeyFile, KeyFileFlags};
+use gstreamer::Caps;
+use gstreamer_pbutils::prelude::*;
+use gstreamer_pbutils::{
+    EncodingAudioProfile, EncodingContainerProfile, EncodingProfile, EncodingTarget, pb_utils_get_codec_description,
+};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+const PROFILE_PREFIX: &str = "profile ";
+
+static REGISTERED: OnceLock<Vec<CustomProfile>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncoderProperty {
+    pub element: String,
+    pub name: String,
+    pub value: String,
+}
+
+impl EncoderProperty {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (target, value) = spec.split_once('=')?;
+        let (element, name) = target.trim().split_once('.')?;
+        Some(Self {
+            element: element.trim().to_string(),
+            name: name.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomProfile {
+    pub id: String,
+    pub name: String,
+    pub codec: String,
+    pub container_caps: Option<String>,
+    pub audio_caps: String,
+    pub preset: Option<String>,
+    pub extension: String,
+    pub lossless: bool,
+    pub properties: Vec<EncoderProperty>,
+}
+
+impl CustomProfile {
+    pub fn profiles_path() -> PathBuf {
+        Config::directory().join("profiles.ini")
+    }
+
+    pub fn targets_dir() -> PathBuf {
+        Config::directory().join("profiles")
+    }
+
+    fn caps_name(caps: &str) -> Option<String> {
+        Caps::from_str(caps)
+            .ok()
+            .and_then(|caps| caps.structure(0).map(|s| s.name().to_string()))
+    }
+
+    fn guess_extension(caps: &str) -> String {
+        let extension = match Self::caps_name(caps).as_deref() {
+            Some("application/ogg") => "ogg",
+            Some("audio/x-matroska") | Some("video/x-matroska") => "mka",
+            Some("video/quicktime") => "m4a",
+            Some("audio/x-wav") => "wav",
+            Some("audio/x-aiff") => "aiff",
+            Some("audio/x-flac") => "flac",
+            Some("audio/x-wavpack") => "wv",
+            Some("audio/mpeg") | Some("application/x-id3") => "mp3",
+            Some("audio/x-opus") => "opus",
+            _ => "audio",
+        };
+        extension.to_string()
+    }
+
+    fn codec_description(audio_caps: &str) -> String {
+        Caps::from_str(audio_caps)
+            .map(|caps| pb_utils_get_codec_description(&caps).to_string())
+            .unwrap_or_else(|_| audio_caps.to_string())
+    }
+
+    fn guess_lossless(audio_caps: &str) -> bool {
+        matches!(
+            Self::caps_name(audio_caps).as_deref(),
+            Some("audio/x-flac") | Some("audio/x-wavpack") | Some("audio/x-alac") | Some("audio/x-raw")
+        )
+    }
+
+    fn validate_caps(caps: &str, id: &str) -> Result<()> {
+        Caps::from_str(caps)
+            .map(|_| ())
+            .map_err(|_| Error::Config(format!("profil '{}' : caps invalides '{}'", id, caps)))
+    }
+
+    fn from_group(key_file: &KeyFile, group: &str, id: &str) -> Result<Self> {
+        let string = |key: &str| key_file.string(group, key).ok().map(|s| s.to_string());
+
+        let audio_caps = string("audio")
+            .ok_or_else(|| Error::Config(format!("profil '{}' : clé 'audio' manquante", id)))?;
+        Self::validate_caps(&audio_caps, id)?;
+        let container_caps = string("container");
+        if let Some(ref caps) = container_caps {
+            Self::validate_caps(caps, id)?;
+        }
+
+        let properties = key_file
+            .string_list(group, "properties")
+            .map(|list| list.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .iter()
+            .map(|spec| {
+                EncoderProperty::parse(spec).ok_or_else(|| {
+                    Error::Config(format!("profil '{}' : propriété invalide '{}' (attendu élément.propriété=valeur)", id, spec))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: id.to_string(),
+            name: string("name").unwrap_or_else(|| id.to_string()),
+            codec: Self::codec_description(&audio_caps),
+            extension: string("extension")
+                .unwrap_or_else(|| Self::guess_extension(container_caps.as_deref().unwrap_or(&audio_caps))),
+            lossless: key_file
+                .boolean(group, "lossless")
+                .unwrap_or_else(|_| Self::guess_lossless(&audio_caps)),
+            preset: string("preset"),
+            container_caps,
+            audio_caps,
+            properties,
+        })
+    }
+
+    pub fn load_key_file(path: &Path) -> Result<Vec<Self>> {
+        let key_file = KeyFile::new();
+        key_file
+            .load_from_file(path, KeyFileFlags::NONE)
+            .map_err(|e| Error::Config(format!("{} : {}", path.display(), e)))?;
+
+        let mut profiles = Vec::new();
+        for group in key_file.groups().iter() {
+            let Some(id) = group.strip_prefix(PROFILE_PREFIX) else {
+                continue;
+            };
+            match Self::from_group(&key_file, group, id) {
+                Ok(profile) => profiles.push(profile),
+                Err(e) => eprintln!("⚠ {} (profil ignoré)", e),
+            }
+        }
+
+        Ok(profiles)
+    }
+
+    fn from_encoding_profile(target: &str, profile: &EncodingProfile) -> Option<Self> {
+        let (container_caps, audio) = match profile.downcast_ref::<EncodingContainerProfile>() {
+            Some(container) => (
+                Some(profile.format().to_string()),
+                container
+                    .profiles()
+                    .into_iter()
+                    .find(|child| child.is::<EncodingAudioProfile>())?,
+            ),
+            None if profile.is::<EncodingAudioProfile>() => (None, profile.clone()),
+            None => return None,
+        };
+
+        let audio_caps = audio.format().to_string();
+        let profile_name = profile.name().map(|n| n.to_string()).unwrap_or_default();
+        Some(Self {
+            id: format!("{}/{}", target, profile_name),
+            name: profile
+                .description()
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| profile_name.clone()),
+            codec: Self::codec_description(&audio_caps),
+            extension: Self::guess_extension(container_caps.as_deref().unwrap_or(&audio_caps)),
+            lossless: Self::guess_lossless(&audio_caps),
+            preset: audio.preset().map(|p| p.to_string()),
+            container_caps,
+            audio_caps,
+            properties: Vec::new(),
+        })
+    }
+
+    pub fn load_target(path: &Path) -> Result<Vec<Self>> {
+        let target = EncodingTarget::load_from_file(path)
+            .map_err(|e| Error::Config(format!("{} : {}", path.display(), e)))?;
+        let target_name = target.name().to_string();
+
+        Ok(target
+            .profiles()
+            .iter()
+            .filter_map(|profile| Self::from_encoding_profile(&target_name, profile))
+            .collect())
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        let mut profiles = Vec::new();
+
+        let path = Self::profiles_path();
+        if path.is_file() {
+            match Self::load_key_file(&path) {
+                Ok(loaded) => profiles.extend(loaded),
+                Err(e) => eprintln!("⚠ {}", e),
+            }
+        }
+
+        let mut targets: Vec<PathBuf> = std::fs::read_dir(Self::targets_dir())
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        targets.retain(|path| path.extension().is_some_and(|extension| extension == "gep"));
+        targets.sort();
+        for path in targets {
+            match Self::load_target(&path) {
+                Ok(loaded) => profiles.extend(loaded),
+                Err(e) => eprintln!("⚠ {}", e),
+            }
+        }
+
+        profiles
+    }
+
+    pub fn install() -> usize {
+        let profiles: Vec<Self> = Self::load_all()
+            .into_iter()
+            .filter(|profile| FormatValidator::check_profile(&profile.create_encoding_profile(), &profile.name))
+            .collect();
+        let count = profiles.len();
+        let _ = REGISTERED.set(profiles);
+        count
+    }
+
+    pub fn registered() -> &'static [CustomProfile] {
+        REGISTERED.get().map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn create_encoding_profile(&self) -> EncodingContainerProfile {
+        let audio_caps = Caps::from_str(&self.audio_caps).unwrap_or_else(|_| Caps::new_any());
+        let container_caps = self
+            .container_caps
+            .as_deref()
+            .and_then(|caps| Caps::from_str(caps).ok())
+            .unwrap_or_else(|| audio_caps.clone());
+
+        let mut audio_profile = EncodingAudioProfile::builder(&audio_caps);
+        if let Some(ref preset) = self.preset {
+            audio_profile = audio_profile.preset(preset);
+        }
+
+        EncodingContainerProfile::builder(&container_caps)
+            .name(&self.id)
+            .add_profile(audio_profile.build())
+            .build()
+    }
+}
@@ -0,0 +1,239 @@
+use super::AudioFormat;
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::utils::make_element;
+use gstreamer::{ClockTime, Element, MessageView, Pipeline, State, TagMergeMode, prelude::*};
+use std::path::{Path, PathBuf};
+
+const POLL_INTERVAL: ClockTime = ClockTime::from_mseconds(250);
+const REFERENCE_LEVEL: f64 = 89.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayGain {
+    pub gain: f64,
+    pub peak: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlbumReplayGain {
+    pub tracks: Vec<(PathBuf, ReplayGain)>,
+    pub album: ReplayGain,
+}
+
+pub struct ReplayGainAnalyzer;
+
+impl ReplayGainAnalyzer {
+    fn wait_for_eos(
+        pipeline: &Pipeline,
+        path: &Path,
+        cancel: &CancellationToken,
+        mut on_tags: impl FnMut(&Element, &gstreamer::TagList),
+    ) -> Result<()> {
+        let bus = pipeline.bus()
+            .ok_or_else(|| Error::Pipeline("Pipeline sans bus".to_string()))?;
+
+        if let Err(e) = pipeline.set_state(State::Playing) {
+            let _ = pipeline.set_state(State::Null);
+            return Err(e.into());
+        }
+
+        let result = loop {
+            if cancel.is_cancelled() {
+                break Err(Error::Cancelled);
+            }
+            let Some(msg) = bus.timed_pop(POLL_INTERVAL) else {
+                continue;
+            };
+            match msg.view() {
+                MessageView::Eos(_) => break Ok(()),
+                MessageView::Error(err) => {
+                    break Err(Error::Pipeline(format!("{} : {}", path.display(), err.error())));
+                }
+                MessageView::Tag(tag_msg) => {
+                    if let Some(src) = msg.src().and_then(|s| s.downcast_ref::<Element>()) {
+                        let tags = tag_msg.tags();
+                        on_tags(src, &tags);
+                    }
+                }
+                _ => {}
+            }
+        };
+
+        pipeline.set_state(State::Null)?;
+        result
+    }
+
+    pub fn analyze_track(path: &Path, cancel: &CancellationToken) -> Result<(ReplayGain, ClockTime)> {
+        let pipeline = Pipeline::new();
+
+        let filesrc = make_element("filesrc")?;
+        filesrc.set_property_from_str("location", &path.to_string_lossy());
+        let decodebin = make_element("decodebin")?;
+        let audioconvert = make_element("audioconvert")?;
+        let audioresample = make_element("audioresample")?;
+        let rganalysis = make_element("rganalysis")?;
+        let sink = make_element("fakesink")?;
+        sink.set_property("sync", false);
+
+        pipeline.add_many([&filesrc, &decodebin, &audioconvert, &audioresample, &rganalysis, &sink])?;
+        filesrc.link(&decodebin)?;
+        Element::link_many([&audioconvert, &audioresample, &rganalysis, &sink])?;
+
+        let convert_clone = audioconvert.clone();
+        decodebin.connect_pad_added(move |_element, src_pad| {
+            let Some(sink_pad) = convert_clone.static_pad("sink") else {
+                return;
+            };
+            if !sink_pad.is_linked() {
+                let _ = src_pad.link(&sink_pad);
+            }
+        });
+
+        let mut gain = None;
+        let mut peak = None;
+        let mut duration = None;
+        let pipeline_clone = pipeline.clone();
+        Self::wait_for_eos(&pipeline, path, cancel, |src, tags| {
+            if src != &rganalysis {
+                return;
+            }
+            if let Some(value) = tags.get::<gstreamer::tags::TrackGain>() {
+                gain = Some(value.get());
+            }
+            if let Some(value) = tags.get::<gstreamer::tags::TrackPeak>() {
+                peak = Some(value.get());
+            }
+            duration = pipeline_clone.query_duration::<ClockTime>().or(duration);
+        })?;
+
+        match (gain, peak) {
+            (Some(gain), Some(peak)) => Ok((ReplayGain { gain, peak }, duration.unwrap_or(ClockTime::ZERO))),
+            _ => Err(Error::Pipeline(format!("{} : analyse ReplayGain sans résultat", path.display()))),
+        }
+    }
+
+    pub fn analyze_album(paths: &[PathBuf], cancel: &CancellationToken) -> Result<AlbumReplayGain> {
+        let mut tracks = Vec::with_capacity(paths.len());
+        let mut weighted_power = 0.0;
+        let mut total_seconds = 0.0;
+        let mut album_peak: f64 = 0.0;
+
+        for (i, path) in paths.iter().enumerate() {
+            println!("  [{}/{}] {}", i + 1, paths.len(), path.display());
+            let (track, duration) = Self::analyze_track(path, cancel)?;
+            let seconds = duration.mseconds() as f64 / 1000.0;
+            let seconds = if seconds > 0.0 { seconds } else { 1.0 };
+
+            weighted_power += seconds * 10f64.powf(-track.gain / 10.0);
+            total_seconds += seconds;
+            album_peak = album_peak.max(track.peak);
+            tracks.push((path.clone(), track));
+        }
+
+        let album_gain = if total_seconds > 0.0 {
+            -10.0 * (weighted_power / total_seconds).log10()
+        } else {
+            0.0
+        };
+
+        Ok(AlbumReplayGain {
+            tracks,
+            album: ReplayGain {
+                gain: album_gain,
+                peak: album_peak,
+            },
+        })
+    }
+}
+
+pub struct ReplayGainTagger;
+
+impl ReplayGainTagger {
+    fn retag_chain(format: AudioFormat) -> Option<(&'static [&'static str], &'static str)> {
+        match format {
+            AudioFormat::Flac => Some((&["flacparse", "flactag"], "flactag")),
+            AudioFormat::Vorbis => Some((&["oggdemux", "vorbisparse", "vorbistag", "oggmux"], "vorbistag")),
+            AudioFormat::Mp3 => Some((&["id3demux", "mpegaudioparse", "id3v2mux"], "id3v2mux")),
+            AudioFormat::Aac => Some((&["qtdemux", "aacparse", "mp4mux"], "mp4mux")),
+            AudioFormat::Wavpack => Some((&["apedemux", "wavpackparse", "apev2mux"], "apev2mux")),
+            AudioFormat::Alac => Some((&["qtdemux", "mp4mux"], "mp4mux")),
+            AudioFormat::OpusMatroska | AudioFormat::FlacMatroska => {
+                Some((&["matroskademux", "matroskamux"], "matroskamux"))
+            }
+            AudioFormat::Opus | AudioFormat::Wav | AudioFormat::Aiff | AudioFormat::Custom(_) => None,
+        }
+    }
+
+    pub fn supports(format: AudioFormat) -> bool {
+        Self::retag_chain(format).is_some()
+    }
+
+    pub fn write_tags(path: &Path, format: AudioFormat, track: ReplayGain, album: ReplayGain) -> Result<()> {
+        let (chain, tagger_name) = Self::retag_chain(format).ok_or_else(|| {
+            Error::Pipeline(format!("écriture des tags ReplayGain non prise en charge pour {}", format.name()))
+        })?;
+
+        let mut tags = gstreamer::TagList::new();
+        {
+            let tags = tags.get_mut().unwrap();
+            tags.add::<gstreamer::tags::TrackGain>(&track.gain, TagMergeMode::Replace);
+            tags.add::<gstreamer::tags::TrackPeak>(&track.peak, TagMergeMode::Replace);
+            tags.add::<gstreamer::tags::AlbumGain>(&album.gain, TagMergeMode::Replace);
+            tags.add::<gstreamer::tags::AlbumPeak>(&album.peak, TagMergeMode::Replace);
+            tags.add::<gstreamer::tags::ReferenceLevel>(&REFERENCE_LEVEL, TagMergeMode::Replace);
+        }
+
+        let temp_path = path.with_file_name(format!(
+            ".{}.rgtmp",
+            path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        ));
+
+        let pipeline = Pipeline::new();
+        let filesrc = make_element("filesrc")?;
+        filesrc.set_property_from_str("location", &path.to_string_lossy());
+        let filesink = make_element("filesink")?;
+        filesink.set_property_from_str("location", &temp_path.to_string_lossy());
+
+        let mut elements = vec![filesrc];
+        for name in chain {
+            let element = make_element(name)?;
+            if *name == tagger_name {
+                let setter = element
+                    .dynamic_cast_ref::<gstreamer::TagSetter>()
+                    .ok_or_else(|| Error::Pipeline(format!("{} ne permet pas d'écrire des tags", name)))?;
+                setter.merge_tags(&tags, TagMergeMode::Replace);
+                setter.set_tag_merge_mode(TagMergeMode::Replace);
+            }
+            elements.push(element);
+        }
+        elements.push(filesink);
+
+        pipeline.add_many(&elements)?;
+        for pair in elements.windows(2) {
+            if pair[0].link(&pair[1]).is_ok() {
+                continue;
+            }
+            let next = pair[1].clone();
+            pair[0].connect_pad_added(move |_element, src_pad| {
+                let Some(sink_pad) = next.static_pad("sink") else {
+                    return;
+                };
+                if !sink_pad.is_linked() {
+                    let _ = src_pad.link(&sink_pad);
+                }
+            });
+        }
+
+        let result = ReplayGainAnalyzer::wait_for_eos(&pipeline, path, &CancellationToken::new(), |_, _| {});
+        match result {
+            Ok(()) => {
+                std::fs::rename(&temp_path, path)?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp_path);
+                Err(e)
+            }
+        }
+    }
+}
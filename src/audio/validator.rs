@@ -1,33 +1,207 @@
 use super::AudioFormat;
-use gstreamer::{ElementFactory, prelude::*};
+use gstreamer::{Caps, ElementFactory, ElementFactoryType, Rank, prelude::*};
+use gstreamer_pbutils::prelude::*;
+use gstreamer_pbutils::missing_plugins::{
+    install_plugins_supported, missing_decoder_installer_detail_new, missing_element_installer_detail_new,
+    missing_encoder_installer_detail_new,
+};
+use gstreamer_pbutils::{
+    EncodingAudioProfile, EncodingContainerProfile, pb_utils_get_codec_description, pb_utils_get_element_description,
+    pb_utils_get_encoder_description,
+};
+
+pub const CD_SOURCES: &[&str] = &["cdparanoiasrc", "cdiocddasrc"];
+const CORE_ELEMENTS: &[&str] = &[
+    "decodebin",
+    "encodebin",
+    "playbin",
+    "audioconvert",
+    "audioresample",
+    "audiorate",
+    "capsfilter",
+    "filesrc",
+    "filesink",
+    "fakesink",
+    "rganalysis",
+    "rgvolume",
+    "rglimiter",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementRole {
+    Muxer,
+    Encoder,
+    Parser,
+    Element,
+}
+
+impl ElementRole {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ElementRole::Muxer => "multiplexeur",
+            ElementRole::Encoder => "encodeur",
+            ElementRole::Parser => "analyseur",
+            ElementRole::Element => "élément",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MissingElement {
+    pub role: ElementRole,
+    pub description: String,
+    pub installer_detail: String,
+    pub required: bool,
+}
+
+impl MissingElement {
+    fn for_caps(role: ElementRole, caps: &Caps, required: bool) -> Self {
+        let (description, installer_detail) = match role {
+            ElementRole::Parser => (
+                pb_utils_get_codec_description(caps),
+                missing_decoder_installer_detail_new(caps),
+            ),
+            _ => (
+                pb_utils_get_encoder_description(caps),
+                missing_encoder_installer_detail_new(caps),
+            ),
+        };
+        Self {
+            role,
+            description: description.to_string(),
+            installer_detail: installer_detail.to_string(),
+            required,
+        }
+    }
+
+    fn for_element(factory: &str) -> Self {
+        Self {
+            role: ElementRole::Element,
+            description: format!("{} ({})", pb_utils_get_element_description(factory), factory),
+            installer_detail: missing_element_installer_detail_new(factory).to_string(),
+            required: true,
+        }
+    }
+
+    pub fn print(&self) {
+        let marker = if self.required { "✗" } else { "⚠" };
+        println!("   {} {} manquant : {}", marker, self.role.label(), self.description);
+        println!("     {}", self.installer_detail);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatReport {
+    pub name: String,
+    pub supported: bool,
+    pub missing: Vec<MissingElement>,
+}
+
+impl FormatReport {
+    pub fn print(&self) {
+        if self.supported {
+            println!("✓ {}", self.name);
+        } else {
+            println!("✗ {}", self.name);
+        }
+        for missing in &self.missing {
+            missing.print();
+        }
+    }
+}
 
 pub struct FormatValidator;
 
 impl FormatValidator {
-    pub fn check_support(format: AudioFormat) -> bool {
-        let encodebin = match ElementFactory::make("encodebin").build() {
-            Ok(e) => e,
-            Err(_) => {
-                eprintln!("   ⚠ encodebin non disponible pour {}", format.name());
-                return false;
-            }
+    fn has_factory(kind: ElementFactoryType, caps: &Caps, produces: bool) -> bool {
+        ElementFactory::factories_with_type(kind, Rank::MARGINAL)
+            .iter()
+            .any(|factory| {
+                if produces {
+                    factory.can_src_any_caps(caps)
+                } else {
+                    factory.can_sink_any_caps(caps) && factory.can_src_any_caps(caps)
+                }
+            })
+    }
+
+    fn find_missing(profile: &EncodingContainerProfile) -> Vec<MissingElement> {
+        let mut missing = Vec::new();
+        let container_caps = profile.format();
+
+        let audio_caps = profile
+            .profiles()
+            .into_iter()
+            .find(|child| child.is::<EncodingAudioProfile>())
+            .map(|audio| audio.format());
+        let Some(audio_caps) = audio_caps else {
+            return missing;
         };
-        
-        let profile = format.create_encoding_profile();
-        encodebin.set_property("profile", &profile);
-        
+
+        let is_raw = audio_caps.structure(0).is_some_and(|s| s.name() == "audio/x-raw");
+        let needs_muxer = !container_caps.can_intersect(&audio_caps);
+
+        if needs_muxer && !Self::has_factory(ElementFactoryType::MUXER | ElementFactoryType::FORMATTER, &container_caps, true) {
+            missing.push(MissingElement::for_caps(ElementRole::Muxer, &container_caps, true));
+        }
+        if !is_raw {
+            if !Self::has_factory(ElementFactoryType::AUDIO_ENCODER, &audio_caps, true) {
+                missing.push(MissingElement::for_caps(ElementRole::Encoder, &audio_caps, true));
+            }
+            if !Self::has_factory(ElementFactoryType::PARSER, &audio_caps, false) {
+                missing.push(MissingElement::for_caps(ElementRole::Parser, &audio_caps, needs_muxer));
+            }
+        }
+
+        missing
+    }
+
+    fn request_pad(profile: &EncodingContainerProfile) -> Option<bool> {
+        let encodebin = ElementFactory::make("encodebin").build().ok()?;
+        encodebin.set_property("profile", profile);
+
         let pad = encodebin.request_pad_simple("audio_%u");
         let supported = pad.is_some();
-        
-        if !supported {
-            eprintln!("   ✗ {} - plugins manquants", format.name());
-        }
-        
         if let Some(pad) = pad {
             encodebin.release_request_pad(&pad);
         }
-        
-        supported
+        Some(supported)
+    }
+
+    pub fn report_profile(profile: &EncodingContainerProfile, name: &str) -> FormatReport {
+        let Some(supported) = Self::request_pad(profile) else {
+            return FormatReport {
+                name: name.to_string(),
+                supported: false,
+                missing: vec![MissingElement::for_element("encodebin")],
+            };
+        };
+
+        let mut missing = Self::find_missing(profile);
+        if supported {
+            missing.retain(|element| !element.required);
+        }
+        FormatReport {
+            name: name.to_string(),
+            supported,
+            missing,
+        }
+    }
+
+    pub fn report(format: AudioFormat) -> FormatReport {
+        Self::report_profile(&format.create_encoding_profile(), format.name())
+    }
+
+    pub fn check_profile(profile: &EncodingContainerProfile, name: &str) -> bool {
+        let report = Self::report_profile(profile, name);
+        if !report.supported {
+            report.print();
+        }
+        report.supported
+    }
+
+    pub fn check_support(format: AudioFormat) -> bool {
+        Self::check_profile(&format.create_encoding_profile(), format.name())
     }
 
     pub fn get_supported_formats() -> Vec<AudioFormat> {
@@ -36,4 +210,59 @@ impl FormatValidator {
             .filter(|&format| Self::check_support(format))
             .collect()
     }
+
+    pub fn find_element(factories: &[&str]) -> Option<String> {
+        factories
+            .iter()
+            .find(|factory| ElementFactory::find(factory).is_some())
+            .map(|factory| factory.to_string())
+    }
+
+    pub fn missing_elements(factories: &[&str]) -> Vec<MissingElement> {
+        factories
+            .iter()
+            .filter(|factory| ElementFactory::find(factory).is_none())
+            .map(|factory| MissingElement::for_element(factory))
+            .collect()
+    }
+
+    pub fn print_environment() {
+        println!("\n=== Environnement GStreamer ===");
+        println!("{}", gstreamer::version_string());
+        let registry = gstreamer::Registry::get();
+        println!(
+            "{} plugin(s), {} élément(s) enregistrés",
+            registry.plugins().len(),
+            ElementFactory::factories_with_type(ElementFactoryType::ANY, Rank::NONE).len()
+        );
+        println!(
+            "Installation automatique des plugins : {}",
+            if install_plugins_supported() { "disponible" } else { "indisponible" }
+        );
+
+        println!("\n=== Éléments de base ===");
+        let missing = Self::missing_elements(CORE_ELEMENTS);
+        if missing.is_empty() {
+            println!("✓ Tous les éléments de base sont présents");
+        }
+        for element in &missing {
+            element.print();
+        }
+
+        println!("\n=== Lecture de CD ===");
+        match Self::find_element(CD_SOURCES) {
+            Some(factory) => println!("✓ Source CD : {}", factory),
+            None => {
+                println!("✗ Aucune source CD disponible");
+                for element in Self::missing_elements(CD_SOURCES) {
+                    element.print();
+                }
+            }
+        }
+
+        println!("\n=== Formats d'encodage ===");
+        for format in AudioFormat::all_formats().into_iter().filter(|format| format.custom().is_none()) {
+            Self::report(format).print();
+        }
+    }
 }
@@ -0,0 +1,269 @@
+use super::AudioChecksum;
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::utils::{Crc32, Md5, make_element};
+use gstreamer::{ClockTime, MessageView, Pipeline, State, prelude::*};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const POLL_INTERVAL: ClockTime = ClockTime::from_mseconds(250);
+const DURATION_TOLERANCE_MS: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityStatus {
+    Intact,
+    Corrupted(String),
+    Truncated { expected_ms: u64, decoded_ms: u64 },
+    Unreadable(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct IntegrityCheck {
+    pub path: PathBuf,
+    pub status: IntegrityStatus,
+    pub verified: Vec<&'static str>,
+}
+
+impl IntegrityCheck {
+    pub fn is_intact(&self) -> bool {
+        self.status == IntegrityStatus::Intact
+    }
+
+    pub fn print(&self) {
+        match &self.status {
+            IntegrityStatus::Intact => println!("✓ {} ({})", self.path.display(), self.verified.join(", ")),
+            IntegrityStatus::Corrupted(reason) => println!("✗ {} : endommagé ({})", self.path.display(), reason),
+            IntegrityStatus::Truncated { expected_ms, decoded_ms } => println!(
+                "✗ {} : tronqué ({} ms décodées sur {} ms)",
+                self.path.display(),
+                decoded_ms,
+                expected_ms
+            ),
+            IntegrityStatus::Unreadable(reason) => println!("✗ {} : illisible ({})", self.path.display(), reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FlacStreamInfo {
+    sample_rate: u64,
+    bits_per_sample: u64,
+    total_samples: u64,
+    md5: [u8; 16],
+}
+
+#[derive(Debug, Default)]
+struct Decoded {
+    crc: Crc32,
+    md5: Option<Md5>,
+    bytes: u64,
+    frames: u64,
+    duration_ns: u64,
+}
+
+pub struct IntegrityChecker;
+
+impl IntegrityChecker {
+    fn read_flac_streaminfo(path: &Path) -> Option<FlacStreamInfo> {
+        let mut file = std::fs::File::open(path).ok()?;
+        let mut header = [0u8; 10];
+        file.read_exact(&mut header[..4]).ok()?;
+
+        if &header[..3] == b"ID3" {
+            file.read_exact(&mut header[4..]).ok()?;
+            let size = header[6..10].iter().fold(0i64, |size, byte| (size << 7) | (*byte as i64 & 0x7F));
+            let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+            file.seek(SeekFrom::Current(size + footer)).ok()?;
+            file.read_exact(&mut header[..4]).ok()?;
+        }
+        if &header[..4] != b"fLaC" {
+            return None;
+        }
+
+        let mut block = [0u8; 38];
+        file.read_exact(&mut block).ok()?;
+        if block[0] & 0x7F != 0 {
+            return None;
+        }
+        let info = &block[4..];
+        let packed = u64::from_be_bytes(info[10..18].try_into().ok()?);
+        Some(FlacStreamInfo {
+            sample_rate: packed >> 44,
+            bits_per_sample: ((packed >> 36) & 0x1F) + 1,
+            total_samples: packed & 0xF_FFFF_FFFF,
+            md5: info[18..34].try_into().ok()?,
+        })
+    }
+
+    fn sample_width(format: &str) -> Option<(usize, usize)> {
+        match format {
+            "S8" | "U8" => Some((1, 1)),
+            "S16LE" => Some((2, 2)),
+            "S24LE" => Some((3, 3)),
+            "S24_32LE" => Some((4, 3)),
+            "S32LE" => Some((4, 4)),
+            _ => None,
+        }
+    }
+
+    fn decode(
+        path: &Path,
+        streaminfo: Option<FlacStreamInfo>,
+        cancel: &CancellationToken,
+    ) -> Result<(Decoded, Option<ClockTime>, Vec<String>)> {
+        let pipeline = Pipeline::new();
+
+        let filesrc = make_element("filesrc")?;
+        filesrc.set_property_from_str("location", &path.to_string_lossy());
+        let decodebin = make_element("decodebin")?;
+        let sink = make_element("fakesink")?;
+        sink.set_property("sync", false);
+        sink.set_property("signal-handoffs", true);
+
+        pipeline.add_many([&filesrc, &decodebin, &sink])?;
+        filesrc.link(&decodebin)?;
+
+        let sink_clone = sink.clone();
+        decodebin.connect_pad_added(move |_element, src_pad| {
+            let Some(sink_pad) = sink_clone.static_pad("sink") else {
+                return;
+            };
+            if !sink_pad.is_linked() {
+                let _ = src_pad.link(&sink_pad);
+            }
+        });
+
+        let decoded = Arc::new(Mutex::new(Decoded {
+            md5: streaminfo.map(|_| Md5::new()),
+            ..Decoded::default()
+        }));
+        let decoded_clone = decoded.clone();
+        sink.connect("handoff", false, move |values| {
+            let (Ok(buffer), Ok(pad)) = (values[1].get::<gstreamer::Buffer>(), values[2].get::<gstreamer::Pad>()) else {
+                return None;
+            };
+            let caps = pad.current_caps()?;
+            let structure = caps.structure(0)?;
+            let channels = structure.get::<i32>("channels").unwrap_or(1).max(1) as usize;
+            let width = structure.get::<&str>("format").ok().and_then(Self::sample_width);
+
+            let (Ok(map), Ok(mut decoded)) = (buffer.map_readable(), decoded_clone.lock()) else {
+                return None;
+            };
+            let data = map.as_slice();
+            decoded.crc.update(data);
+            decoded.bytes += data.len() as u64;
+            decoded.duration_ns += buffer.duration().map(|d| d.nseconds()).unwrap_or(0);
+
+            let (width, packed) = width?;
+            decoded.frames += (data.len() / (width * channels)) as u64;
+            if let Some(ref mut md5) = decoded.md5 {
+                if width == packed {
+                    md5.update(data);
+                } else {
+                    let mut samples = Vec::with_capacity(data.len() / width * packed);
+                    for sample in data.chunks_exact(width) {
+                        samples.extend_from_slice(&sample[..packed]);
+                    }
+                    md5.update(&samples);
+                }
+            }
+            None
+        });
+
+        let bus = pipeline.bus()
+            .ok_or_else(|| Error::Pipeline("Pipeline sans bus".to_string()))?;
+
+        if let Err(e) = pipeline.set_state(State::Playing) {
+            let _ = pipeline.set_state(State::Null);
+            return Err(e.into());
+        }
+
+        let mut warnings = Vec::new();
+        let result = loop {
+            if cancel.is_cancelled() {
+                break Err(Error::Cancelled);
+            }
+            let Some(msg) = bus.timed_pop(POLL_INTERVAL) else {
+                continue;
+            };
+            match msg.view() {
+                MessageView::Eos(_) => break Ok(()),
+                MessageView::Error(err) => break Err(Error::Pipeline(err.error().to_string())),
+                MessageView::Warning(warning) => warnings.push(warning.error().to_string()),
+                _ => {}
+            }
+        };
+        let duration = pipeline.query_duration::<ClockTime>();
+
+        pipeline.set_state(State::Null)?;
+        result?;
+
+        let decoded = decoded
+            .lock()
+            .map(|mut decoded| std::mem::take(&mut *decoded))
+            .map_err(|_| Error::Pipeline("Résultat du décodage indisponible".to_string()))?;
+        Ok((decoded, duration, warnings))
+    }
+
+    pub fn check(path: &Path, expected: Option<AudioChecksum>, cancel: &CancellationToken) -> Result<IntegrityCheck> {
+        let streaminfo = Self::read_flac_streaminfo(path)
+            .filter(|info| info.md5 != [0; 16] && info.bits_per_sample % 8 == 0);
+        let mut verified = vec!["décodage"];
+
+        let status = match Self::decode(path, streaminfo, cancel) {
+            Err(Error::Cancelled) => return Err(Error::Cancelled),
+            Err(Error::Pipeline(reason)) => IntegrityStatus::Unreadable(reason),
+            Err(e) => IntegrityStatus::Unreadable(e.to_string()),
+            Ok((decoded, duration, warnings)) => {
+                let decoded_ms = decoded.duration_ns / 1_000_000;
+                let expected_ms = expected
+                    .map(|checksum| checksum.duration_ms)
+                    .or_else(|| duration.map(|d| d.mseconds()))
+                    .unwrap_or(0);
+
+                let flac_truncated = streaminfo
+                    .filter(|info| info.sample_rate > 0 && decoded.frames < info.total_samples);
+
+                if let Some(info) = flac_truncated {
+                    IntegrityStatus::Truncated {
+                        expected_ms: info.total_samples * 1000 / info.sample_rate,
+                        decoded_ms: decoded.frames * 1000 / info.sample_rate,
+                    }
+                } else if decoded_ms + DURATION_TOLERANCE_MS < expected_ms {
+                    IntegrityStatus::Truncated { expected_ms, decoded_ms }
+                } else if let Some(warning) = warnings.into_iter().next() {
+                    IntegrityStatus::Corrupted(warning)
+                } else if decoded.bytes == 0 {
+                    IntegrityStatus::Unreadable("aucun échantillon audio décodé".to_string())
+                } else {
+                    let mut status = IntegrityStatus::Intact;
+                    if let (Some(info), Some(md5)) = (streaminfo, decoded.md5) {
+                        verified.push("MD5 FLAC");
+                        if md5.finish() != info.md5 {
+                            status = IntegrityStatus::Corrupted("MD5 FLAC différent de celui de l'en-tête".to_string());
+                        }
+                    }
+                    if let Some(checksum) = expected {
+                        verified.push("CRC d'extraction");
+                        if status == IntegrityStatus::Intact && decoded.crc.finish() != checksum.crc32 {
+                            status = IntegrityStatus::Corrupted(format!(
+                                "CRC {:08X} au lieu de {:08X} enregistré à l'extraction",
+                                decoded.crc.finish(),
+                                checksum.crc32
+                            ));
+                        }
+                    }
+                    status
+                }
+            }
+        };
+
+        Ok(IntegrityCheck {
+            path: path.to_path_buf(),
+            status,
+            verified,
+        })
+    }
+}
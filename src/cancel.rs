@@ -0,0 +1,73 @@
+use crate::error::Result;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+type CancelHandler = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerId(u64);
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    next_id: AtomicU64,
+    handlers: Mutex<Vec<(HandlerId, CancelHandler)>>,
+}
+
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        if self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let handlers: Vec<CancelHandler> = match self.inner.handlers.lock() {
+            Ok(handlers) => handlers.iter().map(|(_, handler)| handler.clone()).collect(),
+            Err(_) => return,
+        };
+        for handler in handlers {
+            handler();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn reset(&self) {
+        self.inner.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    pub fn connect(&self, handler: impl Fn() + Send + Sync + 'static) -> HandlerId {
+        let id = HandlerId(self.inner.next_id.fetch_add(1, Ordering::SeqCst));
+        if let Ok(mut handlers) = self.inner.handlers.lock() {
+            handlers.push((id, Arc::new(handler)));
+        }
+        id
+    }
+
+    pub fn disconnect(&self, id: HandlerId) {
+        if let Ok(mut handlers) = self.inner.handlers.lock() {
+            handlers.retain(|(handler_id, _)| *handler_id != id);
+        }
+    }
+
+    pub fn install_signal_handler(&self) -> Result<()> {
+        let token = self.clone();
+        ctrlc::set_handler(move || {
+            if token.is_cancelled() {
+                std::process::exit(130);
+            }
+            println!("\n⏹ Interruption demandée (Ctrl+C à nouveau pour quitter immédiatement)");
+            token.cancel();
+        })?;
+        Ok(())
+    }
+}
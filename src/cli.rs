@@ -0,0 +1,362 @@
+use crate::audio::AudioFormat;
+use crate::encoder::{ConversionOptions, OverwritePolicy};
+use crate::error::{Error, Result};
+use crate::player::{ReplayGainMode, ReplayGainSettings};
+use crate::utils::{TrackSelection, parse_size};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Rip,
+    PlayFiles,
+    PlayCd,
+    Transcode,
+    Sync,
+    Verify,
+    Info,
+    Doctor,
+}
+
+impl Mode {
+    pub fn from_choice(choice: u8) -> Option<Mode> {
+        match choice {
+            1 => Some(Mode::Rip),
+            2 => Some(Mode::PlayFiles),
+            3 => Some(Mode::PlayCd),
+            4 => Some(Mode::Transcode),
+            5 => Some(Mode::Sync),
+            6 => Some(Mode::Verify),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CliOptions {
+    pub mode: Option<Mode>,
+    pub tracks: Option<TrackSelection>,
+    pub overwrite: Option<OverwritePolicy>,
+    pub replaygain: ReplayGainSettings,
+    pub format: Option<AudioFormat>,
+    pub output: Option<PathBuf>,
+    pub query: Option<String>,
+    pub budget: Option<u64>,
+    pub conversion: ConversionOptions,
+    pub device: Option<String>,
+    pub contact: Option<String>,
+    pub files: Vec<PathBuf>,
+    pub help: bool,
+}
+
+impl CliOptions {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "rip" => options.mode = Some(Mode::Rip),
+                "play-files" => options.mode = Some(Mode::PlayFiles),
+                "play-cd" => options.mode = Some(Mode::PlayCd),
+                "transcode" => options.mode = Some(Mode::Transcode),
+                "sync" => options.mode = Some(Mode::Sync),
+                "verify" => options.mode = Some(Mode::Verify),
+                "info" => options.mode = Some(Mode::Info),
+                "doctor" => options.mode = Some(Mode::Doctor),
+                "-t" | "--tracks" => {
+                    let spec = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une sélection de pistes", arg)))?;
+                    options.tracks = Some(TrackSelection::parse(&spec)?);
+                }
+                "--overwrite" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend skip, overwrite ou rename", arg)))?;
+                    options.overwrite = Some(Self::parse_overwrite(&value)?);
+                }
+                "--replaygain" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend off, track ou album", arg)))?;
+                    options.replaygain.mode = Self::parse_replaygain_mode(&value)?;
+                }
+                "--preamp" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une valeur en dB", arg)))?;
+                    options.replaygain.pre_amp_db = Self::parse_preamp(&value)?;
+                }
+                "--no-clip-prevention" => options.replaygain.prevent_clipping = false,
+                "-f" | "--format" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend un format audio", arg)))?;
+                    options.format = Some(Self::parse_format(&value)?);
+                }
+                "-o" | "--output" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend un dossier", arg)))?;
+                    options.output = Some(PathBuf::from(value));
+                }
+                "--query" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend un texte de recherche", arg)))?;
+                    options.query = Some(value);
+                }
+                "--budget" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une taille", arg)))?;
+                    options.budget = Some(Self::parse_budget(&value)?);
+                }
+                "--rate" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une fréquence en Hz", arg)))?;
+                    options.conversion.sample_rate = Some(ConversionOptions::parse_sample_rate(&value)?);
+                }
+                "--bits" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une résolution en bits", arg)))?;
+                    options.conversion.bit_depth = Some(ConversionOptions::parse_bit_depth(&value)?);
+                }
+                "--channels" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend un nombre de canaux", arg)))?;
+                    options.conversion.channels = Some(ConversionOptions::parse_channels(&value)?);
+                }
+                "--downmix" => options.conversion.downmix = true,
+                "-d" | "--device" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend un périphérique, par ex. /dev/sr1", arg)))?;
+                    options.device = Some(value);
+                }
+                "--contact" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::InvalidArgument(format!("{} attend une adresse e-mail ou une URL", arg)))?;
+                    options.contact = Some(value);
+                }
+                "-h" | "--help" => options.help = true,
+                other => {
+                    if let Some(spec) = other.strip_prefix("--tracks=") {
+                        options.tracks = Some(TrackSelection::parse(spec)?);
+                    } else if let Some(value) = other.strip_prefix("--overwrite=") {
+                        options.overwrite = Some(Self::parse_overwrite(value)?);
+                    } else if let Some(value) = other.strip_prefix("--replaygain=") {
+                        options.replaygain.mode = Self::parse_replaygain_mode(value)?;
+                    } else if let Some(value) = other.strip_prefix("--preamp=") {
+                        options.replaygain.pre_amp_db = Self::parse_preamp(value)?;
+                    } else if let Some(value) = other.strip_prefix("--format=") {
+                        options.format = Some(Self::parse_format(value)?);
+                    } else if let Some(value) = other.strip_prefix("--output=") {
+                        options.output = Some(PathBuf::from(value));
+                    } else if let Some(value) = other.strip_prefix("--query=") {
+                        options.query = Some(value.to_string());
+                    } else if let Some(value) = other.strip_prefix("--budget=") {
+                        options.budget = Some(Self::parse_budget(value)?);
+                    } else if let Some(value) = other.strip_prefix("--rate=") {
+                        options.conversion.sample_rate = Some(ConversionOptions::parse_sample_rate(value)?);
+                    } else if let Some(value) = other.strip_prefix("--bits=") {
+                        options.conversion.bit_depth = Some(ConversionOptions::parse_bit_depth(value)?);
+                    } else if let Some(value) = other.strip_prefix("--channels=") {
+                        options.conversion.channels = Some(ConversionOptions::parse_channels(value)?);
+                    } else if let Some(value) = other.strip_prefix("--device=") {
+                        options.device = Some(value.to_string());
+                    } else if let Some(value) = other.strip_prefix("--contact=") {
+                        options.contact = Some(value.to_string());
+                    } else if options.mode == Some(Mode::Info) && !other.starts_with('-') {
+                        options.files.push(PathBuf::from(other));
+                    } else {
+                        return Err(Error::InvalidArgument(format!("option inconnue '{}'", other)));
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn parse_overwrite(value: &str) -> Result<OverwritePolicy> {
+        OverwritePolicy::parse(value)
+            .ok_or_else(|| Error::InvalidArgument(format!("politique d'écrasement inconnue '{}'", value)))
+    }
+
+    fn parse_replaygain_mode(value: &str) -> Result<ReplayGainMode> {
+        ReplayGainMode::parse(value)
+            .ok_or_else(|| Error::InvalidArgument(format!("mode ReplayGain inconnu '{}'", value)))
+    }
+
+    fn parse_format(value: &str) -> Result<AudioFormat> {
+        AudioFormat::parse(value)
+            .ok_or_else(|| Error::InvalidArgument(format!("format audio inconnu '{}'", value)))
+    }
+
+    fn parse_budget(value: &str) -> Result<u64> {
+        parse_size(value).ok_or_else(|| Error::InvalidArgument(format!("taille invalide '{}'", value)))
+    }
+
+    fn parse_preamp(value: &str) -> Result<f64> {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|db| (-60.0..=60.0).contains(db))
+            .ok_or_else(|| Error::InvalidArgument(format!("pré-amplification invalide '{}'", value)))
+    }
+
+    pub fn print_usage() {
+        println!("Usage : hello_gtk [MODE] [OPTIONS]");
+        println!("        hello_gtk info <FICHIER>...");
+        println!();
+        println!("Modes :");
+        println!("  rip          Ripper et transcoder un CD audio");
+        println!("  play-files   Lire des fichiers audio depuis un dossier");
+        println!("  play-cd      Lire les pistes d'un CD audio directement");
+        println!("  transcode    Convertir une bibliothèque de fichiers audio vers un autre format");
+        println!("  sync         Synchroniser une sélection de la bibliothèque vers un baladeur");
+        println!("  verify       Vérifier l'intégrité des fichiers audio d'un dossier");
+        println!("  info         Afficher le codec, le débit et le format de fichiers audio");
+        println!("  doctor       Diagnostiquer l'installation GStreamer et les plugins manquants");
+        println!();
+        println!("Options :");
+        println!("  -t, --tracks <SÉLECTION>   Pistes à traiter, par ex. \"1-3,7,10-\"");
+        println!("  --overwrite <POLITIQUE>    Fichiers déjà présents : skip (défaut), overwrite ou rename");
+        println!("  --replaygain <MODE>        Normalisation à la lecture : off, track (défaut) ou album");
+        println!("  --preamp <DB>              Pré-amplification ReplayGain en dB (-60 à 60)");
+        println!("  --no-clip-prevention       Ne pas limiter les crêtes après application du gain");
+        println!("  -f, --format <FORMAT>      Format : opus, vorbis, flac, mp3, aac, wavpack, alac, wav,");
+        println!("                             aiff, opus-mka, flac-mka ou l'identifiant d'un profil");
+        println!("                             défini dans ~/.config/hello_gtk/profiles.ini");
+        println!("  -o, --output <DOSSIER>     Dossier de destination de l'extraction, de la conversion ou de la synchronisation");
+        println!("  --query <TEXTE>            Ne traiter que les pistes correspondant à la recherche");
+        println!("  --budget <TAILLE>          Espace maximal à occuper lors d'une synchronisation, par ex. 8G");
+        println!("  -d, --device <PÉRIPHÉRIQUE> Lecteur CD à utiliser, par ex. /dev/sr1");
+        println!("  --contact <CONTACT>        Adresse de contact envoyée à MusicBrainz dans le User-Agent");
+        println!("  --rate <HZ>                Fréquence d'échantillonnage des fichiers convertis");
+        println!("  --bits <16|24|32>          Résolution des fichiers convertis, avec dither si elle diminue");
+        println!("  --channels <N>             Nombre de canaux des fichiers convertis");
+        println!("  --downmix                  Mixer en stéréo (ou vers --channels) les sources multicanal");
+        println!("  -h, --help                 Afficher cette aide");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CliOptions, Mode};
+    use crate::audio::AudioFormat;
+    use crate::encoder::OverwritePolicy;
+    use crate::player::ReplayGainMode;
+    use crate::utils::TrackSelection;
+    use std::path::PathBuf;
+
+    fn parse(args: &[&str]) -> crate::error::Result<CliOptions> {
+        CliOptions::parse_from(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_leave_everything_unset() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.mode, None);
+        assert_eq!(options.tracks, None);
+        assert!(!options.help);
+    }
+
+    #[test]
+    fn parses_modes() {
+        assert_eq!(parse(&["rip"]).unwrap().mode, Some(Mode::Rip));
+        assert_eq!(parse(&["play-files"]).unwrap().mode, Some(Mode::PlayFiles));
+        assert_eq!(parse(&["play-cd"]).unwrap().mode, Some(Mode::PlayCd));
+    }
+
+    #[test]
+    fn parses_track_selection_in_both_forms() {
+        let expected = Some(TrackSelection::parse("1-3,7").unwrap());
+        assert_eq!(parse(&["rip", "-t", "1-3,7"]).unwrap().tracks, expected);
+        assert_eq!(parse(&["rip", "--tracks", "1-3,7"]).unwrap().tracks, expected);
+        assert_eq!(parse(&["--tracks=1-3,7", "rip"]).unwrap().tracks, expected);
+    }
+
+    #[test]
+    fn parses_overwrite_policy() {
+        assert_eq!(parse(&["rip"]).unwrap().overwrite, None);
+        assert_eq!(parse(&["rip", "--overwrite", "rename"]).unwrap().overwrite, Some(OverwritePolicy::Rename));
+        assert_eq!(parse(&["--overwrite=overwrite"]).unwrap().overwrite, Some(OverwritePolicy::Overwrite));
+        assert_eq!(parse(&["--overwrite=skip"]).unwrap().overwrite, Some(OverwritePolicy::Skip));
+        assert!(parse(&["--overwrite", "maybe"]).is_err());
+    }
+
+    #[test]
+    fn parses_help() {
+        assert!(parse(&["-h"]).unwrap().help);
+        assert!(parse(&["rip", "--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn rejects_unknown_options_and_missing_values() {
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["rip", "--tracks"]).is_err());
+        assert!(parse(&["--tracks=5-2"]).is_err());
+    }
+
+    #[test]
+    fn parses_value_options() {
+        let options = parse(&[
+            "sync",
+            "-f",
+            "flac",
+            "--output=/media/baladeur",
+            "--budget",
+            "1,5G",
+            "--rate=48000",
+            "--bits",
+            "16",
+            "--downmix",
+            "-d",
+            "/dev/sr1",
+            "--contact=moi@example.org",
+            "--replaygain=album",
+            "--preamp",
+            "-3.5",
+            "--no-clip-prevention",
+        ])
+        .unwrap();
+        assert_eq!(options.mode, Some(Mode::Sync));
+        assert_eq!(options.format, Some(AudioFormat::Flac));
+        assert_eq!(options.output, Some(PathBuf::from("/media/baladeur")));
+        assert_eq!(options.budget, Some(3 << 29));
+        assert_eq!(options.conversion.sample_rate, Some(48_000));
+        assert_eq!(options.conversion.bit_depth, Some(16));
+        assert!(options.conversion.downmix);
+        assert_eq!(options.device.as_deref(), Some("/dev/sr1"));
+        assert_eq!(options.contact.as_deref(), Some("moi@example.org"));
+        assert_eq!(options.replaygain.mode, ReplayGainMode::Album);
+        assert_eq!(options.replaygain.pre_amp_db, -3.5);
+        assert!(!options.replaygain.prevent_clipping);
+    }
+
+    #[test]
+    fn info_mode_collects_files() {
+        let options = parse(&["info", "a.flac", "b.ogg"]).unwrap();
+        assert_eq!(options.files, [PathBuf::from("a.flac"), PathBuf::from("b.ogg")]);
+        assert!(parse(&["rip", "a.flac"]).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        assert!(parse(&["--preamp", "99"]).is_err());
+        assert!(parse(&["--bits=20"]).is_err());
+        assert!(parse(&["--budget", "beaucoup"]).is_err());
+        assert!(parse(&["--replaygain", "loud"]).is_err());
+        assert!(parse(&["--format=mp4"]).is_err());
+    }
+}
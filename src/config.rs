@@ -0,0 +1,194 @@
+use crate::audio::{AudioFormat, EncoderProperty};
+use crate::error::{Error, Result};
+use crate::metadata::{AlbumDetails, CONTACT_VARIABLE, MusicBrainzConfig};
+use glib::{KeyFile, KeyFileFlags};
+use std::path::{Path, PathBuf};
+
+const GENERAL_GROUP: &str = "general";
+const ENCODING_GROUP: &str = "encoding";
+const FILENAMES_GROUP: &str = "filenames";
+const DRIVE_GROUP: &str = "drive";
+const METADATA_GROUP: &str = "metadata";
+
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{number} - {artist} - {album} - {title}";
+const KNOWN_PROVIDERS: &[&str] = &["musicbrainz"];
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub path: PathBuf,
+    pub output_dir: PathBuf,
+    pub formats: Vec<String>,
+    pub encoder_properties: Vec<EncoderProperty>,
+    pub filename_template: String,
+    pub device: Option<String>,
+    pub providers: Vec<String>,
+    pub countries: Vec<String>,
+    pub contact: Option<String>,
+}
+
+impl Config {
+    pub fn directory() -> PathBuf {
+        glib::user_config_dir().join(env!("CARGO_PKG_NAME"))
+    }
+
+    pub fn default_path() -> PathBuf {
+        Self::directory().join("settings.ini")
+    }
+
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            output_dir: PathBuf::from("output"),
+            formats: Vec::new(),
+            encoder_properties: Vec::new(),
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            device: None,
+            providers: vec!["musicbrainz".to_string()],
+            countries: Vec::new(),
+            contact: None,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut config = Self::new(path);
+        if !path.exists() {
+            return Ok(config);
+        }
+
+        let key_file = KeyFile::new();
+        key_file
+            .load_from_file(path, KeyFileFlags::NONE)
+            .map_err(|e| Error::Config(format!("{} : {}", path.display(), e)))?;
+
+        let string = |group: &str, key: &str| {
+            key_file
+                .string(group, key)
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let list = |group: &str, key: &str| {
+            key_file.string_list(group, key).ok().map(|list| {
+                list.iter()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        if let Some(output_dir) = string(GENERAL_GROUP, "output-dir") {
+            config.output_dir = PathBuf::from(output_dir);
+        }
+        if let Some(formats) = list(ENCODING_GROUP, "formats") {
+            config.formats = formats;
+        }
+        if let Some(properties) = list(ENCODING_GROUP, "properties") {
+            config.encoder_properties = properties
+                .iter()
+                .map(|spec| {
+                    EncoderProperty::parse(spec).ok_or_else(|| {
+                        Error::Config(format!("propriété d'encodeur invalide '{}' (attendu élément.propriété=valeur)", spec))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+        }
+        if let Some(template) = string(FILENAMES_GROUP, "template") {
+            config.filename_template = template;
+        }
+        config.device = string(DRIVE_GROUP, "device");
+        if let Some(providers) = list(METADATA_GROUP, "providers") {
+            config.providers = providers.iter().map(|provider| provider.to_lowercase()).collect();
+        }
+        if let Some(countries) = list(METADATA_GROUP, "countries") {
+            config.countries = countries.iter().map(|country| country.to_uppercase()).collect();
+        }
+        config.contact = string(METADATA_GROUP, "contact");
+
+        for provider in config.providers.iter().filter(|p| !KNOWN_PROVIDERS.contains(&p.as_str())) {
+            eprintln!("⚠ Fournisseur de métadonnées inconnu ignoré : {}", provider);
+        }
+
+        Ok(config)
+    }
+
+    pub fn load_default() -> Self {
+        let path = Self::default_path();
+        let config = match Self::load(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("⚠ {}, valeurs par défaut utilisées", e);
+                return Self::new(&path);
+            }
+        };
+        if !path.exists()
+            && let Err(e) = config.save()
+        {
+            eprintln!("⚠ Configuration non enregistrée : {}", e);
+        }
+        config
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let key_file = KeyFile::new();
+
+        key_file.set_string(GENERAL_GROUP, "output-dir", &self.output_dir.to_string_lossy());
+        key_file.set_string(ENCODING_GROUP, "formats", &self.formats.join(";"));
+        let properties: Vec<String> = self
+            .encoder_properties
+            .iter()
+            .map(|p| format!("{}.{}={}", p.element, p.name, p.value))
+            .collect();
+        key_file.set_string(ENCODING_GROUP, "properties", &properties.join(";"));
+        key_file.set_string(FILENAMES_GROUP, "template", &self.filename_template);
+        key_file.set_string(DRIVE_GROUP, "device", self.device.as_deref().unwrap_or(""));
+        key_file.set_string(METADATA_GROUP, "providers", &self.providers.join(";"));
+        key_file.set_string(METADATA_GROUP, "countries", &self.countries.join(";"));
+        key_file.set_string(METADATA_GROUP, "contact", self.contact.as_deref().unwrap_or(""));
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        key_file
+            .save_to_file(&self.path)
+            .map_err(|e| Error::Config(format!("{} : {}", self.path.display(), e)))
+    }
+
+    pub fn preferred_formats(&self) -> Vec<AudioFormat> {
+        self.formats
+            .iter()
+            .filter_map(|id| {
+                let format = AudioFormat::parse(id);
+                if format.is_none() {
+                    eprintln!("⚠ Format préféré inconnu ignoré : {}", id);
+                }
+                format
+            })
+            .collect()
+    }
+
+    pub fn uses_provider(&self, provider: &str) -> bool {
+        self.providers.iter().any(|p| p == provider)
+    }
+
+    pub fn musicbrainz(&self, contact: Option<&str>) -> MusicBrainzConfig {
+        let mut config = MusicBrainzConfig::default();
+        let contact = contact
+            .map(str::to_string)
+            .or_else(|| std::env::var(CONTACT_VARIABLE).ok().filter(|c| !c.trim().is_empty()))
+            .or_else(|| self.contact.clone());
+        if let Some(contact) = contact {
+            config.contact = contact;
+        }
+        config
+    }
+
+    pub fn sort_by_country(&self, albums: &mut [AlbumDetails]) {
+        albums.sort_by_key(|album| {
+            album
+                .country
+                .as_ref()
+                .and_then(|country| self.countries.iter().position(|c| c.eq_ignore_ascii_case(country)))
+                .unwrap_or(self.countries.len())
+        });
+    }
+}
@@ -0,0 +1,77 @@
+use super::{BUS_NAME, OBJECT_PATH};
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use gio::{BusNameOwnerFlags, BusType, DBusConnection, DBusNodeInfo, RegistrationId};
+use glib::{MainContext, MainLoop};
+
+const INTERFACE_NAME: &str = "org.anrouxel.HelloGtk.Control";
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="org.anrouxel.HelloGtk.Control">
+    <method name="Cancel"/>
+  </interface>
+</node>
+"#;
+
+pub struct ControlService;
+
+impl ControlService {
+    pub fn register(connection: &DBusConnection, token: CancellationToken) -> Result<RegistrationId> {
+        let node = DBusNodeInfo::for_xml(INTERFACE_XML).map_err(|e| Error::DBus(e.to_string()))?;
+        let interface = node
+            .lookup_interface(INTERFACE_NAME)
+            .ok_or_else(|| Error::DBus(format!("interface {} introuvable", INTERFACE_NAME)))?;
+
+        connection
+            .register_object(OBJECT_PATH, &interface)
+            .method_call(move |_connection, _sender, _path, _interface, method, _params, invocation| {
+                match method {
+                    "Cancel" => {
+                        println!("\n⏹ Annulation demandée via D-Bus");
+                        token.cancel();
+                        invocation.return_value(None);
+                    }
+                    _ => invocation.return_dbus_error(
+                        "org.freedesktop.DBus.Error.UnknownMethod",
+                        &format!("Méthode inconnue : {}", method),
+                    ),
+                }
+            })
+            .build()
+            .map_err(|e| Error::DBus(e.to_string()))
+    }
+
+    pub fn spawn_on_session_bus(token: CancellationToken) {
+        std::thread::spawn(move || {
+            let context = MainContext::new();
+            let result = context.with_thread_default(|| {
+                let connection = match gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>) {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        eprintln!("⚠ Bus de session D-Bus indisponible : {}", e);
+                        return;
+                    }
+                };
+
+                if let Err(e) = Self::register(&connection, token) {
+                    eprintln!("⚠ {}", e);
+                    return;
+                }
+
+                let _owner = gio::bus_own_name_on_connection(
+                    &connection,
+                    BUS_NAME,
+                    BusNameOwnerFlags::NONE,
+                    |_connection, _name| {},
+                    |_connection, name| eprintln!("⚠ Nom D-Bus {} non disponible", name),
+                );
+
+                MainLoop::new(Some(&context), false).run();
+            });
+
+            if let Err(e) = result {
+                eprintln!("⚠ Service D-Bus non démarré : {}", e);
+            }
+        });
+    }
+}
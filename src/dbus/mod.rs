@@ -0,0 +1,8 @@
+pub mod control;
+pub mod mpris;
+
+pub use control::ControlService;
+pub use mpris::MprisService;
+
+pub const BUS_NAME: &str = "org.anrouxel.HelloGtk";
+pub const OBJECT_PATH: &str = "/org/anrouxel/HelloGtk";
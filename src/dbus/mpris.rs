@@ -0,0 +1,455 @@
+use crate::error::{Error, Result};
+use crate::player::{ListenerId, PlaybackStatus, PlayerEvent, QueuePlayer, RepeatMode};
+use gio::{BusNameOwnerFlags, BusType, DBusConnection, DBusNodeInfo, OwnerId, RegistrationId};
+use glib::prelude::*;
+use glib::variant::ObjectPath;
+use glib::{Variant, VariantDict};
+use gstreamer::ClockTime;
+
+pub const MPRIS_BUS_NAME: &str = "org.mpris.MediaPlayer2.hello_gtk";
+const MPRIS_OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const TRACK_PATH_PREFIX: &str = "/org/anrouxel/HelloGtk/track";
+const NO_TRACK_PATH: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="org.mpris.MediaPlayer2">
+    <method name="Raise"/>
+    <method name="Quit"/>
+    <property name="CanQuit" type="b" access="read"/>
+    <property name="CanRaise" type="b" access="read"/>
+    <property name="HasTrackList" type="b" access="read"/>
+    <property name="Identity" type="s" access="read"/>
+    <property name="SupportedUriSchemes" type="as" access="read"/>
+    <property name="SupportedMimeTypes" type="as" access="read"/>
+  </interface>
+  <interface name="org.mpris.MediaPlayer2.Player">
+    <method name="Next"/>
+    <method name="Previous"/>
+    <method name="Pause"/>
+    <method name="PlayPause"/>
+    <method name="Stop"/>
+    <method name="Play"/>
+    <method name="Seek">
+      <arg direction="in" name="Offset" type="x"/>
+    </method>
+    <method name="SetPosition">
+      <arg direction="in" name="TrackId" type="o"/>
+      <arg direction="in" name="Position" type="x"/>
+    </method>
+    <method name="OpenUri">
+      <arg direction="in" name="Uri" type="s"/>
+    </method>
+    <signal name="Seeked">
+      <arg name="Position" type="x"/>
+    </signal>
+    <property name="PlaybackStatus" type="s" access="read"/>
+    <property name="LoopStatus" type="s" access="readwrite"/>
+    <property name="Rate" type="d" access="readwrite"/>
+    <property name="Shuffle" type="b" access="readwrite"/>
+    <property name="Metadata" type="a{sv}" access="read"/>
+    <property name="Volume" type="d" access="readwrite"/>
+    <property name="Position" type="x" access="read"/>
+    <property name="MinimumRate" type="d" access="read"/>
+    <property name="MaximumRate" type="d" access="read"/>
+    <property name="CanGoNext" type="b" access="read"/>
+    <property name="CanGoPrevious" type="b" access="read"/>
+    <property name="CanPlay" type="b" access="read"/>
+    <property name="CanPause" type="b" access="read"/>
+    <property name="CanSeek" type="b" access="read"/>
+    <property name="CanControl" type="b" access="read"/>
+  </interface>
+</node>
+"#;
+
+pub struct MprisService {
+    connection: DBusConnection,
+    player: QueuePlayer,
+    listener: Option<ListenerId>,
+    registrations: Vec<RegistrationId>,
+    owner: Option<OwnerId>,
+}
+
+impl MprisService {
+    pub fn register(connection: &DBusConnection, player: &QueuePlayer) -> Result<Self> {
+        let node = DBusNodeInfo::for_xml(INTERFACE_XML).map_err(|e| Error::DBus(e.to_string()))?;
+        let mut service = Self {
+            connection: connection.clone(),
+            player: player.clone(),
+            listener: None,
+            registrations: Vec::new(),
+            owner: None,
+        };
+
+        for name in [ROOT_INTERFACE, PLAYER_INTERFACE] {
+            let interface = node
+                .lookup_interface(name)
+                .ok_or_else(|| Error::DBus(format!("interface {} introuvable", name)))?;
+
+            let method_player = player.clone();
+            let get_player = player.clone();
+            let set_player = player.clone();
+
+            let id = connection
+                .register_object(MPRIS_OBJECT_PATH, &interface)
+                .method_call(move |_connection, _sender, _path, _interface, method, params, invocation| {
+                    match Self::call_method(&method_player, method, &params) {
+                        Ok(()) => invocation.return_value(None),
+                        Err(message) => invocation.return_dbus_error(
+                            "org.freedesktop.DBus.Error.NotSupported",
+                            &message,
+                        ),
+                    }
+                })
+                .property(move |_connection, _sender, _path, _interface, property| {
+                    Self::property(&get_player, property).unwrap_or_else(|| false.to_variant())
+                })
+                .set_property(move |_connection, _sender, _path, _interface, property, value| {
+                    Self::set_property(&set_player, property, &value)
+                })
+                .build()
+                .map_err(|e| Error::DBus(e.to_string()))?;
+            service.registrations.push(id);
+        }
+
+        let connection = connection.clone();
+        service.listener = Some(player.connect_event(move |player, event| Self::notify(&connection, player, event)));
+
+        Ok(service)
+    }
+
+    pub fn on_session_bus(player: &QueuePlayer) -> Result<Self> {
+        let connection = gio::bus_get_sync(BusType::Session, None::<&gio::Cancellable>)?;
+        let mut service = Self::register(&connection, player)?;
+        service.owner = Some(gio::bus_own_name_on_connection(
+            &connection,
+            MPRIS_BUS_NAME,
+            BusNameOwnerFlags::NONE,
+            |_connection, _name| {},
+            |_connection, name| eprintln!("⚠ Nom D-Bus {} non disponible", name),
+        ));
+        Ok(service)
+    }
+
+    fn call_method(player: &QueuePlayer, method: &str, params: &Variant) -> std::result::Result<(), String> {
+        let result = match method {
+            "Raise" => Ok(()),
+            "Quit" | "Stop" => {
+                player.stop();
+                Ok(())
+            }
+            "Next" => {
+                player.next();
+                Ok(())
+            }
+            "Previous" => {
+                player.previous();
+                Ok(())
+            }
+            "Pause" => player.pause(),
+            "Play" => player.resume(),
+            "PlayPause" => player.toggle_pause(),
+            "Seek" => {
+                let offset = params.child_value(0).get::<i64>().unwrap_or(0);
+                let current = Self::micros(player.position().unwrap_or(ClockTime::ZERO));
+                player.seek_to(ClockTime::from_useconds((current + offset).max(0) as u64))
+            }
+            "SetPosition" => {
+                let track_id = params.child_value(0).str().map(str::to_string).unwrap_or_default();
+                let position = params.child_value(1).get::<i64>().unwrap_or(-1);
+                if track_id != Self::track_path(player) || position < 0 {
+                    return Ok(());
+                }
+                player.seek_to(ClockTime::from_useconds(position as u64))
+            }
+            "OpenUri" => return Err("OpenUri n'est pas pris en charge".to_string()),
+            other => return Err(format!("Méthode inconnue : {}", other)),
+        };
+        result.map_err(|e| e.to_string())
+    }
+
+    fn property(player: &QueuePlayer, property: &str) -> Option<Variant> {
+        let value = match property {
+            "CanQuit" | "CanPlay" | "CanPause" | "CanSeek" | "CanControl" => true.to_variant(),
+            "CanRaise" | "HasTrackList" => false.to_variant(),
+            "Identity" => "Hello GTK".to_variant(),
+            "SupportedUriSchemes" => vec!["file", "cdda"].to_variant(),
+            "SupportedMimeTypes" => Vec::<String>::new().to_variant(),
+            "PlaybackStatus" => Self::status_name(player.status()).to_variant(),
+            "LoopStatus" => Self::loop_status(player.repeat()).to_variant(),
+            "Rate" | "MinimumRate" | "MaximumRate" => 1.0f64.to_variant(),
+            "Shuffle" => player.is_shuffled().to_variant(),
+            "Metadata" => Self::metadata(player),
+            "Volume" => player.volume().to_variant(),
+            "Position" => Self::micros(player.position().unwrap_or(ClockTime::ZERO)).to_variant(),
+            "CanGoNext" => player.has_next().to_variant(),
+            "CanGoPrevious" => player.has_previous().to_variant(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn set_property(player: &QueuePlayer, property: &str, value: &Variant) -> bool {
+        match property {
+            "LoopStatus" => {
+                let repeat = match value.str() {
+                    Some("None") => RepeatMode::Off,
+                    Some("Track") => RepeatMode::One,
+                    Some("Playlist") => RepeatMode::All,
+                    _ => return false,
+                };
+                player.set_repeat(repeat);
+                true
+            }
+            "Shuffle" => match value.get::<bool>() {
+                Some(shuffle) => {
+                    player.set_shuffle(shuffle);
+                    true
+                }
+                None => false,
+            },
+            "Volume" => match value.get::<f64>() {
+                Some(volume) => {
+                    player.set_volume(volume);
+                    true
+                }
+                None => false,
+            },
+            "Rate" => value.get::<f64>().is_some(),
+            _ => false,
+        }
+    }
+
+    fn notify(connection: &DBusConnection, player: &QueuePlayer, event: PlayerEvent) {
+        let properties: &[&str] = match event {
+            PlayerEvent::TrackChanged => &["Metadata", "CanGoNext", "CanGoPrevious"],
+            PlayerEvent::StatusChanged => &["PlaybackStatus"],
+            PlayerEvent::OptionsChanged => &["LoopStatus", "Shuffle", "CanGoNext", "CanGoPrevious"],
+            PlayerEvent::VolumeChanged => &["Volume"],
+            PlayerEvent::Seeked(position) => {
+                let result = connection.emit_signal(
+                    None,
+                    MPRIS_OBJECT_PATH,
+                    PLAYER_INTERFACE,
+                    "Seeked",
+                    Some(&(Self::micros(position),).to_variant()),
+                );
+                if let Err(e) = result {
+                    eprintln!("⚠ Signal MPRIS non émis : {}", e);
+                }
+                return;
+            }
+        };
+
+        let changed = VariantDict::new(None);
+        for property in properties {
+            if let Some(value) = Self::property(player, property) {
+                changed.insert_value(property, &value);
+            }
+        }
+
+        let parameters = Variant::tuple_from_iter([
+            PLAYER_INTERFACE.to_variant(),
+            changed.end(),
+            Vec::<String>::new().to_variant(),
+        ]);
+        if let Err(e) = connection.emit_signal(
+            None,
+            MPRIS_OBJECT_PATH,
+            PROPERTIES_INTERFACE,
+            "PropertiesChanged",
+            Some(&parameters),
+        ) {
+            eprintln!("⚠ Signal MPRIS non émis : {}", e);
+        }
+    }
+
+    fn metadata(player: &QueuePlayer) -> Variant {
+        let now_playing = player.now_playing();
+        let metadata = VariantDict::new(None);
+
+        if let Ok(track_id) = ObjectPath::try_from(Self::track_path(player)) {
+            metadata.insert_value("mpris:trackid", &track_id.to_variant());
+        }
+        if !now_playing.title.is_empty() {
+            metadata.insert_value("xesam:title", &now_playing.title.to_variant());
+        }
+        if let Some(artist) = now_playing.artist {
+            metadata.insert_value("xesam:artist", &vec![artist].to_variant());
+        }
+        if let Some(album) = now_playing.album {
+            metadata.insert_value("xesam:album", &album.to_variant());
+        }
+        if let Some(duration) = now_playing.duration {
+            metadata.insert_value("mpris:length", &Self::micros(duration).to_variant());
+        }
+
+        metadata.end()
+    }
+
+    fn track_path(player: &QueuePlayer) -> String {
+        match player.status() {
+            PlaybackStatus::Stopped => NO_TRACK_PATH.to_string(),
+            _ => format!("{}/{}", TRACK_PATH_PREFIX, player.now_playing().index),
+        }
+    }
+
+    fn status_name(status: PlaybackStatus) -> &'static str {
+        match status {
+            PlaybackStatus::Playing => "Playing",
+            PlaybackStatus::Paused => "Paused",
+            PlaybackStatus::Stopped => "Stopped",
+        }
+    }
+
+    fn loop_status(repeat: RepeatMode) -> &'static str {
+        match repeat {
+            RepeatMode::Off => "None",
+            RepeatMode::One => "Track",
+            RepeatMode::All => "Playlist",
+        }
+    }
+
+    fn micros(time: ClockTime) -> i64 {
+        time.useconds() as i64
+    }
+}
+
+impl Drop for MprisService {
+    fn drop(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            self.player.disconnect_event(listener);
+        }
+        if let Some(owner) = self.owner.take() {
+            gio::bus_unown_name(owner);
+        }
+        for id in self.registrations.drain(..) {
+            let _ = self.connection.unregister_object(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayQueue;
+    use gio::{DBusCallFlags, DBusConnectionFlags, DBusSignalFlags, TestDBus, TestDBusFlags};
+    use glib::{MainContext, MainLoop};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn connect(address: &str) -> DBusConnection {
+        DBusConnection::for_address_sync(
+            address,
+            DBusConnectionFlags::AUTHENTICATION_CLIENT | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+            None,
+            None::<&gio::Cancellable>,
+        )
+        .unwrap()
+    }
+
+    fn call(
+        client: &DBusConnection,
+        service: &str,
+        interface: &str,
+        method: &str,
+        parameters: Option<&Variant>,
+    ) -> std::result::Result<Variant, glib::Error> {
+        let reply = Rc::new(RefCell::new(None));
+        let slot = reply.clone();
+        client.call(
+            Some(service),
+            MPRIS_OBJECT_PATH,
+            interface,
+            method,
+            parameters,
+            None,
+            DBusCallFlags::NONE,
+            -1,
+            None::<&gio::Cancellable>,
+            move |result| *slot.borrow_mut() = Some(result),
+        );
+        let context = MainContext::ref_thread_default();
+        while reply.borrow().is_none() {
+            context.iteration(true);
+        }
+        reply.take().unwrap()
+    }
+
+    fn get(client: &DBusConnection, service: &str, property: &str) -> std::result::Result<Variant, glib::Error> {
+        let reply = call(
+            client,
+            service,
+            PROPERTIES_INTERFACE,
+            "Get",
+            Some(&(PLAYER_INTERFACE, property).to_variant()),
+        )?;
+        Ok(reply.child_value(0).as_variant().unwrap())
+    }
+
+    fn exercise_service(player: &QueuePlayer, address: &str) {
+        let connection = connect(address);
+        let client = connect(address);
+        let service_name = connection.unique_name().unwrap().to_string();
+        let service = MprisService::register(&connection, player).unwrap();
+
+        let status = get(&client, &service_name, "PlaybackStatus").unwrap();
+        assert_eq!(status.str(), Some("Stopped"));
+
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let received = changes.clone();
+        let subscription = client.signal_subscribe(
+            Some(&service_name),
+            Some(PROPERTIES_INTERFACE),
+            Some("PropertiesChanged"),
+            Some(MPRIS_OBJECT_PATH),
+            None,
+            DBusSignalFlags::NONE,
+            move |_connection, _sender, _path, _interface, _signal, parameters| {
+                received.borrow_mut().push(parameters.clone());
+            },
+        );
+        call(&client, "org.freedesktop.DBus", "org.freedesktop.DBus", "GetId", None).unwrap();
+
+        player.set_shuffle(true);
+        let context = MainContext::ref_thread_default();
+        while changes.borrow().is_empty() {
+            context.iteration(true);
+        }
+        let parameters = changes.borrow()[0].clone();
+        assert_eq!(parameters.child_value(0).str(), Some(PLAYER_INTERFACE));
+        let changed = VariantDict::new(Some(&parameters.child_value(1)));
+        assert_eq!(changed.lookup::<bool>("Shuffle").unwrap(), Some(true));
+        client.signal_unsubscribe(subscription);
+
+        assert!(call(&client, &service_name, PLAYER_INTERFACE, "Next", None).is_ok());
+        assert!(call(&client, &service_name, PLAYER_INTERFACE, "OpenUri", Some(&("file:///x.flac",).to_variant())).is_err());
+
+        drop(service);
+        assert!(get(&client, &service_name, "PlaybackStatus").is_err());
+    }
+
+    #[test]
+    fn exposes_the_player_on_a_private_bus() {
+        gstreamer::init().unwrap();
+        let Ok(player) = QueuePlayer::new(PlayQueue::new(Vec::new()), &MainLoop::new(None, false)) else {
+            eprintln!("playbin3 indisponible, test ignoré");
+            return;
+        };
+        if glib::find_program_in_path("dbus-daemon").is_none() {
+            eprintln!("dbus-daemon introuvable, test ignoré");
+            return;
+        }
+
+        let bus = TestDBus::new(TestDBusFlags::NONE);
+        bus.up();
+        let address = bus.bus_address().unwrap().to_string();
+        MainContext::new()
+            .with_thread_default(|| exercise_service(&player, &address))
+            .unwrap();
+        bus.down();
+    }
+}
@@ -0,0 +1,218 @@
+use crate::error::{Error, Result};
+use gstreamer::{Caps, List};
+
+const SUPPORTED_DEPTHS: &[u32] = &[16, 24, 32];
+const INTEGER_FORMATS: &[(&str, u32)] = &[("S16LE", 16), ("S24LE", 24), ("S24_32LE", 24), ("S32LE", 32)];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConversionOptions {
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u32>,
+    pub channels: Option<u32>,
+    pub downmix: bool,
+}
+
+impl ConversionOptions {
+    pub fn parse_sample_rate(value: &str) -> Result<u32> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|rate| (8_000..=384_000).contains(rate))
+            .ok_or_else(|| Error::InvalidArgument(format!("fréquence '{}' invalide (8000 à 384000 Hz)", value)))
+    }
+
+    pub fn parse_bit_depth(value: &str) -> Result<u32> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|depth| SUPPORTED_DEPTHS.contains(depth))
+            .ok_or_else(|| Error::InvalidArgument(format!("résolution '{}' invalide (16, 24 ou 32 bits)", value)))
+    }
+
+    pub fn parse_channels(value: &str) -> Result<u32> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|channels| (1..=8).contains(channels))
+            .ok_or_else(|| Error::InvalidArgument(format!("nombre de canaux '{}' invalide (1 à 8)", value)))
+    }
+
+    pub fn is_passthrough(&self) -> bool {
+        self.sample_rate.is_none() && self.bit_depth.is_none() && self.channels.is_none() && !self.downmix
+    }
+
+    fn raw_format(depth: u32) -> &'static str {
+        match depth {
+            16 => "S16LE",
+            24 => "S24LE",
+            _ => "S32LE",
+        }
+    }
+
+    pub fn dithering(&self) -> &'static str {
+        if self.bit_depth.is_some() { "tpdf" } else { "none" }
+    }
+
+    pub fn caps(&self) -> Option<Caps> {
+        if self.is_passthrough() {
+            return None;
+        }
+
+        let mut caps = Caps::builder("audio/x-raw");
+        if let Some(rate) = self.sample_rate {
+            caps = caps.field("rate", rate as i32);
+        }
+        if let Some(depth) = self.bit_depth {
+            caps = caps.field("format", Self::raw_format(depth));
+        }
+        match (self.channels, self.downmix) {
+            (Some(channels), false) => caps = caps.field("channels", channels as i32),
+            (Some(1), true) => caps = caps.field("channels", 1),
+            (channels, true) => {
+                caps = caps.field("channels", gstreamer::IntRange::new(1, channels.unwrap_or(2) as i32));
+            }
+            (None, false) => {}
+        }
+        Some(caps.build())
+    }
+
+    pub fn preserving_caps(rate: u32, channels: u32, depth: u32) -> Option<Caps> {
+        let formats: Vec<&str> = INTEGER_FORMATS
+            .iter()
+            .filter(|(_, width)| *width >= depth)
+            .map(|(name, _)| *name)
+            .collect();
+        if rate == 0 || channels == 0 || depth == 0 || formats.is_empty() {
+            return None;
+        }
+
+        Some(
+            Caps::builder("audio/x-raw")
+                .field("format", List::new(formats))
+                .field("rate", rate as i32)
+                .field("channels", channels as i32)
+                .build(),
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(rate) = self.sample_rate {
+            parts.push(format!("{} Hz", rate));
+        }
+        if let Some(depth) = self.bit_depth {
+            parts.push(format!("{} bits", depth));
+        }
+        match (self.channels, self.downmix) {
+            (Some(channels), false) => parts.push(format!("{} canal(aux)", channels)),
+            (channels, true) => parts.push(format!("mixage vers {} canal(aux) au plus", channels.unwrap_or(2))),
+            (None, false) => {}
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConversionOptions;
+    use gstreamer::{Caps, IntRange, List};
+
+    fn caps(options: ConversionOptions) -> Option<Caps> {
+        gstreamer::init().unwrap();
+        options.caps()
+    }
+
+    fn formats(caps: &Caps) -> Vec<String> {
+        caps.structure(0)
+            .unwrap()
+            .get::<List>("format")
+            .unwrap()
+            .iter()
+            .map(|value| value.get::<String>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn passthrough_has_no_caps() {
+        assert!(ConversionOptions::default().is_passthrough());
+        assert_eq!(caps(ConversionOptions::default()), None);
+        assert_eq!(ConversionOptions::default().dithering(), "none");
+    }
+
+    #[test]
+    fn constrains_rate_depth_and_channels() {
+        let options = ConversionOptions {
+            sample_rate: Some(48_000),
+            bit_depth: Some(24),
+            channels: Some(2),
+            downmix: false,
+        };
+        let caps = caps(options).unwrap();
+        let structure = caps.structure(0).unwrap();
+        assert_eq!(structure.name(), "audio/x-raw");
+        assert_eq!(structure.get::<i32>("rate").unwrap(), 48_000);
+        assert_eq!(structure.get::<&str>("format").unwrap(), "S24LE");
+        assert_eq!(structure.get::<i32>("channels").unwrap(), 2);
+        assert_eq!(options.dithering(), "tpdf");
+    }
+
+    #[test]
+    fn leaves_unrequested_fields_open() {
+        let caps = caps(ConversionOptions {
+            sample_rate: Some(44_100),
+            ..Default::default()
+        })
+        .unwrap();
+        let structure = caps.structure(0).unwrap();
+        assert!(structure.has_field("rate"));
+        assert!(!structure.has_field("format"));
+        assert!(!structure.has_field("channels"));
+    }
+
+    #[test]
+    fn downmix_caps_the_channel_count() {
+        let stereo = caps(ConversionOptions {
+            downmix: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let range = stereo.structure(0).unwrap().get::<IntRange<i32>>("channels").unwrap();
+        assert_eq!((range.min(), range.max()), (1, 2));
+
+        let mono = caps(ConversionOptions {
+            channels: Some(1),
+            downmix: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(mono.structure(0).unwrap().get::<i32>("channels").unwrap(), 1);
+    }
+
+    #[test]
+    fn preserving_caps_never_narrow_the_source_depth() {
+        gstreamer::init().unwrap();
+        let cd = ConversionOptions::preserving_caps(44_100, 2, 16).unwrap();
+        assert_eq!(formats(&cd), ["S16LE", "S24LE", "S24_32LE", "S32LE"]);
+        assert_eq!(cd.structure(0).unwrap().get::<i32>("rate").unwrap(), 44_100);
+        assert_eq!(cd.structure(0).unwrap().get::<i32>("channels").unwrap(), 2);
+
+        let hires = ConversionOptions::preserving_caps(96_000, 6, 24).unwrap();
+        assert_eq!(formats(&hires), ["S24LE", "S24_32LE", "S32LE"]);
+
+        assert_eq!(ConversionOptions::preserving_caps(44_100, 2, 0), None);
+        assert_eq!(ConversionOptions::preserving_caps(44_100, 2, 64), None);
+    }
+
+    #[test]
+    fn validates_command_line_values() {
+        assert_eq!(ConversionOptions::parse_sample_rate("96000").unwrap(), 96_000);
+        assert!(ConversionOptions::parse_sample_rate("100").is_err());
+        assert_eq!(ConversionOptions::parse_bit_depth("24").unwrap(), 24);
+        assert!(ConversionOptions::parse_bit_depth("20").is_err());
+        assert_eq!(ConversionOptions::parse_channels("6").unwrap(), 6);
+        assert!(ConversionOptions::parse_channels("0").is_err());
+    }
+}
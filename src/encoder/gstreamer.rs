@@ -1,26 +1,72 @@
-use crate::audio::AudioFormat;
+use crate::audio::{AudioFormat, EncoderProperty, FormatValidator, MediaInfo};
+use crate::audio::validator::CD_SOURCES;
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
 use crate::metadata::{AlbumDetails, TrackDetails};
-use crate::utils::sanitize_filename;
-use discid::DiscId;
-use glib::{ControlFlow, MainLoop};
-use gstreamer::{ElementFactory, MessageView, Pipeline, State, prelude::*};
+use crate::utils::{make_element, sanitize_filename};
+use super::ConversionOptions;
+use gstreamer::{Caps, ClockTime, Element, MessageView, Pipeline, State, prelude::*};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncoderSource {
+    CdTrack(u32),
+    File(PathBuf),
+}
+
+#[derive(Clone, Copy)]
+pub struct TrackJob<'a> {
+    pub track: &'a TrackDetails,
+    pub album: &'a AlbumDetails,
+    pub output_filename: &'a str,
+    pub format: AudioFormat,
+}
 
 pub trait AudioEncoder {
     fn transcode(
         &self,
-        disc: &DiscId,
-        track: &TrackDetails,
-        album: &AlbumDetails,
+        job: &TrackJob,
+        progress: &mut dyn FnMut(ClockTime, Option<ClockTime>),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>>;
+
+    fn transcode_file(
+        &self,
+        input: &Path,
         output_filename: &str,
         format: AudioFormat,
-    ) -> Result<(), Box<dyn std::error::Error>>;
+        conversion: &ConversionOptions,
+        progress: &mut dyn FnMut(ClockTime, Option<ClockTime>),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>>;
 }
 
-pub struct GStreamerEncoder;
+const PROGRESS_INTERVAL: ClockTime = ClockTime::from_mseconds(250);
+const CD_SAMPLE_RATE: u32 = 44_100;
+const CD_CHANNELS: u32 = 2;
+const CD_DEPTH: u32 = 16;
+
+pub struct GStreamerEncoder {
+    properties: Vec<EncoderProperty>,
+    device: Option<String>,
+}
 
 impl GStreamerEncoder {
     pub fn new() -> Self {
-        Self
+        Self {
+            properties: Vec::new(),
+            device: None,
+        }
+    }
+
+    pub fn with_device(mut self, device: Option<String>) -> Self {
+        self.device = device;
+        self
+    }
+
+    pub fn with_properties(mut self, properties: Vec<EncoderProperty>) -> Self {
+        self.properties = properties;
+        self
     }
 
     fn apply_metadata(
@@ -28,7 +74,7 @@ impl GStreamerEncoder {
         pipeline: &Pipeline,
         track: &TrackDetails,
         album: &AlbumDetails,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<()> {
         use gstreamer::tags;
         
         let mut tag_list = gstreamer::TagList::new();
@@ -48,93 +94,258 @@ impl GStreamerEncoder {
         Ok(())
     }
 
-    pub fn create_output_filename(track: &TrackDetails, album: &AlbumDetails, format: AudioFormat) -> String {
+    pub fn create_output_filename(
+        track: &TrackDetails,
+        album: &AlbumDetails,
+        format: AudioFormat,
+        template: &str,
+    ) -> String {
         let track_num = format!("{:02}", track.number);
         let title = sanitize_filename(&track.title);
         let artist = sanitize_filename(track.artist.as_ref().unwrap_or(&"Unknown".to_string()));
         let album_title = sanitize_filename(&album.title);
+        let year = album
+            .release_date
+            .as_deref()
+            .map(|date| date.chars().take(4).collect::<String>())
+            .unwrap_or_default();
         let extension = format.file_extension();
         
-        format!("{} - {} - {} - {}.{}", track_num, artist, album_title, title, extension)
+        let name = template
+            .replace("{number}", &track_num)
+            .replace("{artist}", &artist)
+            .replace("{album}", &album_title)
+            .replace("{title}", &title)
+            .replace("{year}", &year);
+        format!("{}.{}", name, extension)
     }
-}
 
-impl AudioEncoder for GStreamerEncoder {
-    fn transcode(
+    fn remove_partial_output(output_filename: &str) {
+        match std::fs::remove_file(output_filename) {
+            Ok(()) => println!("Fichier partiel supprimé : {}", output_filename),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("Impossible de supprimer le fichier partiel {} : {}", output_filename, e),
+        }
+    }
+
+    fn create_source(&self, pipeline: &Pipeline, source: &EncoderSource, next: &Element) -> Result<()> {
+        match source {
+            EncoderSource::CdTrack(number) => {
+                let factory = FormatValidator::find_element(CD_SOURCES)
+                    .ok_or_else(|| Error::MissingPlugin { element: CD_SOURCES.join(" ou ") })?;
+                let cdsrc = make_element(&factory)?;
+                if let Some(ref device) = self.device {
+                    cdsrc.set_property("device", device);
+                }
+                cdsrc.set_property("track", *number);
+                pipeline.add(&cdsrc)?;
+                cdsrc.link(next)?;
+            }
+            EncoderSource::File(path) => {
+                let filesrc = make_element("filesrc")?;
+                filesrc.set_property_from_str("location", &path.to_string_lossy());
+                let decodebin = make_element("decodebin")?;
+                pipeline.add_many([&filesrc, &decodebin])?;
+                filesrc.link(&decodebin)?;
+
+                let next = next.clone();
+                decodebin.connect_pad_added(move |_element, src_pad| {
+                    let is_audio = src_pad
+                        .current_caps()
+                        .and_then(|caps| caps.structure(0).map(|s| s.name().starts_with("audio/")))
+                        .unwrap_or(false);
+                    let Some(sink_pad) = next.static_pad("sink") else {
+                        return;
+                    };
+                    if is_audio && !sink_pad.is_linked() {
+                        let _ = src_pad.link(&sink_pad);
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn preserving_caps(source: &EncoderSource) -> Option<Caps> {
+        match source {
+            EncoderSource::CdTrack(_) => ConversionOptions::preserving_caps(CD_SAMPLE_RATE, CD_CHANNELS, CD_DEPTH),
+            EncoderSource::File(path) => {
+                let uri = glib::filename_to_uri(path, None).ok()?;
+                let info = MediaInfo::discover(&uri).ok()?;
+                ConversionOptions::preserving_caps(info.sample_rate?, info.channels?, info.depth?)
+            }
+        }
+    }
+
+    fn build_pipeline(
         &self,
-        _disc: &DiscId,
-        track: &TrackDetails,
-        album: &AlbumDetails,
+        source: &EncoderSource,
         output_filename: &str,
         format: AudioFormat,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Transcodage de la piste {} : {} (format: {})", track.number, track.title, format.name());
-        
+        conversion: &ConversionOptions,
+    ) -> Result<Pipeline> {
         let pipeline = Pipeline::new();
         
-        let source = ElementFactory::make_with_name("cdparanoiasrc", Some("src")).unwrap();
-        source.set_property("track", track.number as u32);
-        
-        let audiorate = ElementFactory::make("audiorate").build().unwrap();
-        let audioconvert = ElementFactory::make("audioconvert").build().unwrap();
-        let audioresample = ElementFactory::make("audioresample").build().unwrap();
+        let audiorate = make_element("audiorate")?;
+        let audioconvert = make_element("audioconvert")?;
+        audioconvert.set_property_from_str("dithering", "none");
+        let audioresample = make_element("audioresample")?;
+        let requantize = make_element("audioconvert")?;
+        requantize.set_property_from_str("dithering", conversion.dithering());
+        let capsfilter = make_element("capsfilter")?;
+        let caps = match conversion.caps() {
+            Some(caps) => Some(caps),
+            None if format.is_lossless() => Self::preserving_caps(source),
+            None => None,
+        };
+        if let Some(caps) = caps {
+            capsfilter.set_property("caps", &caps);
+        }
         
-        let encodebin = ElementFactory::make("encodebin").build().unwrap();
+        let encodebin = make_element("encodebin")?;
         let profile = format.create_encoding_profile();
         encodebin.set_property("profile", &profile);
         
-        let sink = ElementFactory::make("filesink").build().unwrap();
+        let sink = make_element("filesink")?;
         sink.set_property_from_str("location", output_filename);
 
-        pipeline.add_many(&[
-            &source,
+        let mut properties = format.encoder_properties().to_vec();
+        properties.extend(self.properties.iter().cloned());
+        if !properties.is_empty() {
+            pipeline.connect_deep_element_added(move |_pipeline, _bin, element| {
+                let Some(factory) = element.factory() else {
+                    return;
+                };
+                for property in properties.iter().filter(|p| p.element == factory.name().as_str()) {
+                    if element.find_property(&property.name).is_some() {
+                        element.set_property_from_str(&property.name, &property.value);
+                    } else {
+                        eprintln!("⚠ {} n'a pas de propriété '{}'", property.element, property.name);
+                    }
+                }
+            });
+        }
+
+        pipeline.add_many([
             &audiorate,
             &audioconvert,
             &audioresample,
+            &requantize,
+            &capsfilter,
             &encodebin,
             &sink,
         ])?;
 
-        source.link(&audiorate)?;
+        self.create_source(&pipeline, source, &audiorate)?;
         audiorate.link(&audioconvert)?;
         audioconvert.link(&audioresample)?;
+        audioresample.link(&requantize)?;
+        requantize.link(&capsfilter)?;
         
         let audio_pad = encodebin.request_pad_simple("audio_%u")
-            .ok_or_else(|| format!("Impossible de créer un pad audio pour encodebin. Le format {} n'est peut-être pas supporté ou les plugins nécessaires ne sont pas installés.", format.name()))?;
-        let audioresample_src_pad = audioresample.static_pad("src")
-            .ok_or("Impossible d'obtenir le pad source d'audioresample")?;
-        audioresample_src_pad.link(&audio_pad)?;
+            .ok_or_else(|| Error::Pipeline(format!("Impossible de créer un pad audio pour encodebin. Le format {} n'est peut-être pas supporté ou les plugins nécessaires ne sont pas installés.", format.name())))?;
+        let capsfilter_src_pad = capsfilter.static_pad("src")
+            .ok_or_else(|| Error::Pipeline("Impossible d'obtenir le pad source du filtre de format".to_string()))?;
+        capsfilter_src_pad.link(&audio_pad)?;
         
         encodebin.link(&sink)?;
 
-        self.apply_metadata(&pipeline, track, album)?;
+        Ok(pipeline)
+    }
+
+    fn run_pipeline(
+        &self,
+        pipeline: &Pipeline,
+        output_filename: &str,
+        progress: &mut dyn FnMut(ClockTime, Option<ClockTime>),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>> {
+        let bus = pipeline.bus()
+            .ok_or_else(|| Error::Pipeline("Pipeline sans bus".to_string()))?;
 
-        let bus = pipeline.bus().expect("Pipeline without bus");
-        let main_loop = MainLoop::new(None, false);
-        let ml_clone = main_loop.clone();
+        if let Err(e) = pipeline.set_state(State::Playing) {
+            let _ = pipeline.set_state(State::Null);
+            Self::remove_partial_output(output_filename);
+            return Err(e.into());
+        }
         
-        let track_number = track.number;
+        let mut failure = None;
+        let mut warnings = Vec::new();
         
-        let _bus_watch = bus.add_watch(move |_bus, msg| {
-            match msg.view() {
-                MessageView::Eos(_) => {
-                    println!("Transcodage terminé pour la piste {}", track_number);
-                    ml_clone.quit();
-                }
-                MessageView::Error(err) => {
-                    eprintln!("Erreur lors du transcodage: {} ({:?})", err.error(), err.debug());
-                    ml_clone.quit();
+        loop {
+            if cancel.is_cancelled() {
+                failure = Some(Error::Cancelled);
+                break;
+            }
+            
+            if let Some(msg) = bus.timed_pop(PROGRESS_INTERVAL) {
+                match msg.view() {
+                    MessageView::Eos(_) => break,
+                    MessageView::Error(err) => {
+                        let message = match err.debug() {
+                            Some(debug) => format!("{} ({})", err.error(), debug),
+                            None => err.error().to_string(),
+                        };
+                        failure = Some(Error::Pipeline(message));
+                        break;
+                    }
+                    MessageView::Warning(warning) => {
+                        warnings.push(warning.error().to_string());
+                    }
+                    _ => {}
                 }
-                MessageView::StateChanged(_) => {}
-                _ => {}
             }
-            ControlFlow::Continue
-        })?;
-
-        pipeline.set_state(State::Playing)?;
-        main_loop.run();
+            
+            if let Some(position) = pipeline.query_position::<ClockTime>() {
+                progress(position, pipeline.query_duration::<ClockTime>());
+            }
+        }
+        
         pipeline.set_state(State::Null)?;
         
-        Ok(())
+        if let Some(e) = failure {
+            Self::remove_partial_output(output_filename);
+            return Err(e);
+        }
+        
+        Ok(warnings)
+    }
+}
+
+impl AudioEncoder for GStreamerEncoder {
+    fn transcode(
+        &self,
+        job: &TrackJob,
+        progress: &mut dyn FnMut(ClockTime, Option<ClockTime>),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>> {
+        println!("Transcodage de la piste {} : {} (format: {})", job.track.number, job.track.title, job.format.name());
+        
+        let pipeline = self.build_pipeline(
+            &EncoderSource::CdTrack(job.track.number),
+            job.output_filename,
+            job.format,
+            &ConversionOptions::default(),
+        )?;
+        self.apply_metadata(&pipeline, job.track, job.album)?;
+        self.run_pipeline(&pipeline, job.output_filename, progress, cancel)
+    }
+
+    fn transcode_file(
+        &self,
+        input: &Path,
+        output_filename: &str,
+        format: AudioFormat,
+        conversion: &ConversionOptions,
+        progress: &mut dyn FnMut(ClockTime, Option<ClockTime>),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<String>> {
+        let pipeline = self.build_pipeline(
+            &EncoderSource::File(input.to_path_buf()),
+            output_filename,
+            format,
+            conversion,
+        )?;
+        self.run_pipeline(&pipeline, output_filename, progress, cancel)
     }
 }
@@ -0,0 +1,245 @@
+use crate::audio::{AudioFormat, EncoderProperty};
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::library::LibraryTrack;
+use super::{AudioEncoder, ConversionOptions, GStreamerEncoder, ProgressCallback, ProgressEvent, RipReport, TrackReport, TrackStatus};
+use gstreamer::ClockTime;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const ARTWORK_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+
+pub struct LibraryTranscoder {
+    encoder: Box<dyn AudioEncoder>,
+    source_root: PathBuf,
+    output_root: PathBuf,
+    format: AudioFormat,
+    conversion: ConversionOptions,
+    progress: Option<ProgressCallback>,
+    cancel: CancellationToken,
+}
+
+impl LibraryTranscoder {
+    pub fn new(source_root: impl Into<PathBuf>, output_root: impl Into<PathBuf>, format: AudioFormat) -> Self {
+        Self {
+            encoder: Box::new(GStreamerEncoder::new()),
+            source_root: source_root.into(),
+            output_root: output_root.into(),
+            format,
+            conversion: ConversionOptions::default(),
+            progress: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_encoder_properties(mut self, properties: Vec<EncoderProperty>) -> Self {
+        self.encoder = Box::new(GStreamerEncoder::new().with_properties(properties));
+        self
+    }
+
+    pub fn with_conversion(mut self, conversion: ConversionOptions) -> Self {
+        self.conversion = conversion;
+        self
+    }
+
+    pub fn with_progress(mut self, callback: impl Fn(&ProgressEvent) + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    fn emit(&self, event: ProgressEvent) {
+        if let Some(ref callback) = self.progress {
+            callback(&event);
+        }
+    }
+
+    pub fn relative_path(&self, source: &Path) -> PathBuf {
+        match source.strip_prefix(&self.source_root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => PathBuf::from(source.file_name().unwrap_or_default()),
+        }
+    }
+
+    pub fn output_path(&self, source: &Path) -> PathBuf {
+        self.output_root
+            .join(self.relative_path(source))
+            .with_extension(self.format.file_extension())
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn is_up_to_date(source: &Path, output: &Path) -> bool {
+        match (Self::modified(source), Self::modified(output)) {
+            (Some(source), Some(output)) => output >= source,
+            _ => false,
+        }
+    }
+
+    fn is_artwork(path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| ARTWORK_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    fn copy_artwork(&self, directories: &BTreeSet<PathBuf>) -> usize {
+        let mut copied = 0;
+        for directory in directories {
+            let Ok(entries) = std::fs::read_dir(directory) else {
+                continue;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if !path.is_file() || !Self::is_artwork(&path) {
+                    continue;
+                }
+                let target = self.output_root.join(self.relative_path(&path));
+                if Self::is_up_to_date(&path, &target) {
+                    continue;
+                }
+                let result = match target.parent() {
+                    Some(parent) => std::fs::create_dir_all(parent).and_then(|_| std::fs::copy(&path, &target)),
+                    None => std::fs::copy(&path, &target),
+                };
+                match result {
+                    Ok(_) => copied += 1,
+                    Err(e) => eprintln!("⚠ Pochette non copiée {} : {}", path.display(), e),
+                }
+            }
+        }
+        copied
+    }
+
+    pub fn transcode(&self, tracks: &[LibraryTrack]) -> Result<RipReport> {
+        std::fs::create_dir_all(&self.output_root)?;
+
+        println!("Conversion de {} fichier(s) vers {}", tracks.len(), self.format.name());
+        println!("Dossier de destination : {}", self.output_root.display());
+
+        let mut report = RipReport::new();
+        let mut pending = Vec::new();
+
+        for (i, track) in tracks.iter().enumerate() {
+            let number = i as u32 + 1;
+            let output = self.output_path(&track.path);
+            if Self::is_up_to_date(&track.path, &output) {
+                report.push(TrackReport {
+                    number,
+                    title: self.relative_path(&track.path).display().to_string(),
+                    output_filename: output.to_string_lossy().to_string(),
+                    status: TrackStatus::Skipped("déjà à jour".to_string()),
+                    warnings: Vec::new(),
+                });
+            } else {
+                pending.push((number, track, output));
+            }
+        }
+
+        let total = pending.len();
+        let mut remaining_ms: u64 = pending.iter().map(|(_, track, _)| track.duration_ms.unwrap_or(0)).sum();
+        let started = Instant::now();
+        let mut audio_done_ms: u64 = 0;
+
+        for (index, (number, track, output)) in pending.into_iter().enumerate() {
+            if self.cancel.is_cancelled() {
+                report.cancelled = true;
+                break;
+            }
+
+            let title = self.relative_path(&track.path).display().to_string();
+            let output_filename = output.to_string_lossy().to_string();
+            let track_ms = track.duration_ms.unwrap_or(0);
+            remaining_ms = remaining_ms.saturating_sub(track_ms);
+
+            self.emit(ProgressEvent::Started {
+                number,
+                title: title.clone(),
+                index,
+                total,
+            });
+
+            let mut on_progress = |position: ClockTime, duration: Option<ClockTime>| {
+                let position_ms = position.mseconds();
+                let duration_ms = duration.map(|d| d.mseconds()).unwrap_or(track_ms);
+                let fraction = if duration_ms > 0 {
+                    (position_ms as f64 / duration_ms as f64).min(1.0)
+                } else {
+                    0.0
+                };
+
+                let elapsed_ms = started.elapsed().as_millis() as u64;
+                let speed = if elapsed_ms > 0 {
+                    (audio_done_ms + position_ms) as f64 / elapsed_ms as f64
+                } else {
+                    0.0
+                };
+                let disc_eta = (speed > 0.0).then(|| {
+                    let left_ms = remaining_ms + duration_ms.saturating_sub(position_ms);
+                    Duration::from_millis((left_ms as f64 / speed) as u64)
+                });
+
+                self.emit(ProgressEvent::Progress {
+                    fraction,
+                    speed,
+                    disc_eta,
+                });
+            };
+
+            let result = match output.parent() {
+                Some(parent) => std::fs::create_dir_all(parent).map_err(Error::from),
+                None => Ok(()),
+            }
+            .and_then(|_| {
+                self.encoder
+                    .transcode_file(
+                        &track.path,
+                        &output_filename,
+                        self.format,
+                        &self.conversion,
+                        &mut on_progress,
+                        &self.cancel,
+                    )
+            });
+            let (status, warnings) = match result {
+                Ok(warnings) => (TrackStatus::Success, warnings),
+                Err(e) => (TrackStatus::Failed(e), Vec::new()),
+            };
+            audio_done_ms += track_ms;
+
+            self.emit(ProgressEvent::Finished);
+
+            if let TrackStatus::Failed(Error::Cancelled) = status {
+                println!("⏹ Conversion de {} annulée", title);
+                report.cancelled = true;
+            }
+
+            report.push(TrackReport {
+                number,
+                title,
+                output_filename,
+                status,
+                warnings,
+            });
+        }
+
+        if !report.cancelled {
+            let directories: BTreeSet<PathBuf> = tracks
+                .iter()
+                .filter_map(|track| track.path.parent().map(Path::to_path_buf))
+                .collect();
+            let copied = self.copy_artwork(&directories);
+            if copied > 0 {
+                println!("{} pochette(s) copiée(s)", copied);
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -1,45 +1,396 @@
-use crate::audio::AudioFormat;
-use crate::metadata::AlbumDetails;
-use super::{AudioEncoder, GStreamerEncoder};
+use crate::audio::{AudioChecksum, AudioFormat, ReplayGainAnalyzer, ReplayGainTagger};
+use crate::cancel::CancellationToken;
+use crate::config::DEFAULT_FILENAME_TEMPLATE;
+use crate::error::{Error, Result};
+use crate::library::{LibraryIndex, LibraryTrack};
+use crate::metadata::{AlbumDetails, TrackDetails};
+use crate::player::MediaSource;
+use crate::playlist::{Playlist, PlaylistEntry};
+use crate::utils::{TrackSelection, sanitize_filename};
+use super::{AudioEncoder, GStreamerEncoder, OverwritePolicy, ProgressCallback, ProgressEvent, RipReport, RipState, TrackJob, TrackReport, TrackState, TrackStatus};
 use discid::DiscId;
-use std::error::Error;
+use gstreamer::ClockTime;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 pub struct TranscodeManager {
     encoder: Box<dyn AudioEncoder>,
+    output_dir: PathBuf,
+    filename_template: String,
+    overwrite_policy: OverwritePolicy,
+    progress: Option<ProgressCallback>,
+    cancel: CancellationToken,
+    library: Option<Rc<RefCell<LibraryIndex>>>,
 }
 
 impl TranscodeManager {
     pub fn new() -> Self {
         Self {
             encoder: Box::new(GStreamerEncoder::new()),
+            output_dir: PathBuf::from("output"),
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            overwrite_policy: OverwritePolicy::Skip,
+            progress: None,
+            cancel: CancellationToken::new(),
+            library: None,
         }
     }
 
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = output_dir.into();
+        self
+    }
+
+    pub fn with_filename_template(mut self, template: impl Into<String>) -> Self {
+        self.filename_template = template.into();
+        self
+    }
+
+    pub fn with_encoder(mut self, encoder: impl AudioEncoder + 'static) -> Self {
+        self.encoder = Box::new(encoder);
+        self
+    }
+
+    pub fn with_overwrite_policy(mut self, policy: OverwritePolicy) -> Self {
+        self.overwrite_policy = policy;
+        self
+    }
+
+    pub fn with_progress(mut self, callback: impl Fn(&ProgressEvent) + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    pub fn with_library_index(mut self, index: Rc<RefCell<LibraryIndex>>) -> Self {
+        self.library = Some(index);
+        self
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    fn emit(&self, event: ProgressEvent) {
+        if let Some(ref callback) = self.progress {
+            callback(&event);
+        }
+    }
+
+    fn add_to_library(&self, path: &Path, track: &TrackDetails, album: &AlbumDetails, format: AudioFormat) {
+        let Some(ref library) = self.library else {
+            return;
+        };
+
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut entry = LibraryTrack::from_path(path);
+        entry.title = track.title.clone();
+        entry.artist = track.artist.clone().or_else(|| album.artist.clone());
+        entry.album_artist = album.artist.clone();
+        entry.album = Some(album.title.clone());
+        entry.track_number = Some(track.number);
+        entry.disc_number = album.disc_number;
+        entry.duration_ms = track.duration.map(u64::from);
+        entry.format = Some(format.name().to_string());
+        library.borrow_mut().insert(entry);
+    }
+
+    fn album_files(&self, report: &RipReport) -> Vec<(u32, PathBuf)> {
+        let mut files: Vec<(u32, PathBuf)> = report
+            .tracks
+            .iter()
+            .filter(|t| matches!(t.status, TrackStatus::Success | TrackStatus::Skipped(_)))
+            .filter_map(|t| {
+                Path::new(&t.output_filename)
+                    .file_name()
+                    .map(|name| (t.number, self.output_dir.join(name)))
+            })
+            .collect();
+        files.sort_by_key(|(number, _)| *number);
+        files
+    }
+
+    fn apply_replaygain(&self, format: AudioFormat, report: &RipReport) -> Result<()> {
+        if !ReplayGainTagger::supports(format) {
+            println!("ReplayGain : écriture des tags non prise en charge pour {}", format.name());
+            return Ok(());
+        }
+
+        let paths: Vec<PathBuf> = self.album_files(report).into_iter().map(|(_, path)| path).collect();
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        println!("\nAnalyse ReplayGain de l'album ({} pistes)...", paths.len());
+        let analysis = ReplayGainAnalyzer::analyze_album(&paths, &self.cancel)?;
+        println!(
+            "Gain album : {:+.2} dB (crête {:.3})",
+            analysis.album.gain, analysis.album.peak
+        );
+
+        for (path, track) in &analysis.tracks {
+            if let Err(e) = ReplayGainTagger::write_tags(path, format, *track, analysis.album) {
+                eprintln!("⚠ Tags ReplayGain non écrits pour {} : {}", path.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_album_playlist(&self, album: &AlbumDetails, report: &RipReport) -> Result<Option<PathBuf>> {
+        let mut playlist = Playlist::new(Some(album.title.clone()));
+        for (number, path) in self.album_files(report) {
+            let mut entry = PlaylistEntry::new(MediaSource::File(path.to_string_lossy().to_string()));
+            entry.album = Some(album.title.clone());
+            if let Some(track) = album.tracks.iter().find(|t| t.number == number) {
+                entry.title = Some(track.title.clone());
+                entry.artist = track.artist.clone().or_else(|| album.artist.clone());
+                entry.duration_ms = track.duration.map(u64::from);
+            }
+            playlist.entries.push(entry);
+        }
+
+        if playlist.is_empty() {
+            return Ok(None);
+        }
+
+        let artist = sanitize_filename(album.artist.as_deref().unwrap_or("Unknown"));
+        let path = self
+            .output_dir
+            .join(format!("{} - {}.m3u8", artist, sanitize_filename(&album.title)));
+        playlist.save(&path)?;
+        Ok(Some(path))
+    }
+
+    fn is_intact(&self, path: &Path, expected: &TrackState) -> bool {
+        match AudioChecksum::compute(path, &self.cancel) {
+            Ok(checksum) => checksum.crc32 == expected.crc32 && checksum.duration_ms == expected.duration_ms,
+            Err(_) => false,
+        }
+    }
+
+    fn unique_path(path: &Path) -> PathBuf {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+        (2..)
+            .map(|n| parent.join(format!("{} ({}).{}", stem, n, extension)))
+            .find(|candidate| !candidate.exists())
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    fn output_path(&self, track: &TrackDetails, album: &AlbumDetails, format: AudioFormat) -> PathBuf {
+        self.output_dir
+            .join(GStreamerEncoder::create_output_filename(track, album, format, &self.filename_template))
+    }
+
+    pub fn existing_outputs(&self, album: &AlbumDetails, format: AudioFormat, selection: &TrackSelection) -> usize {
+        album
+            .tracks
+            .iter()
+            .filter(|t| selection.contains(t.number))
+            .filter(|t| self.output_path(t, album, format).exists())
+            .count()
+    }
+
+    fn plan<'a>(
+        &self,
+        album: &'a AlbumDetails,
+        format: AudioFormat,
+        selection: &TrackSelection,
+        state: &mut RipState,
+        report: &mut RipReport,
+    ) -> Vec<(&'a TrackDetails, PathBuf)> {
+        let mut pending = Vec::new();
+        
+        for track in album.tracks.iter().filter(|t| selection.contains(t.number)) {
+            let mut path = self.output_path(track, album, format);
+            
+            match self.overwrite_policy {
+                OverwritePolicy::Skip => {
+                    if let Some(done) = state.completed(track.number) {
+                        if self.is_intact(&self.output_dir.join(&done.filename), done) {
+                            println!("↷ Piste {} déjà extraite et vérifiée", track.number);
+                            report.push(TrackReport {
+                                number: track.number,
+                                title: track.title.clone(),
+                                output_filename: done.filename.clone(),
+                                status: TrackStatus::Skipped("déjà extraite".to_string()),
+                                warnings: Vec::new(),
+                            });
+                            continue;
+                        }
+                        println!("Piste {} incomplète ou modifiée, nouvelle extraction", track.number);
+                    } else if path.exists() {
+                        println!("↷ Piste {} : {} existe déjà, fichier conservé", track.number, path.display());
+                        report.push(TrackReport {
+                            number: track.number,
+                            title: track.title.clone(),
+                            output_filename: path.to_string_lossy().to_string(),
+                            status: TrackStatus::Skipped("fichier existant".to_string()),
+                            warnings: Vec::new(),
+                        });
+                        continue;
+                    }
+                }
+                OverwritePolicy::Rename if path.exists() => path = Self::unique_path(&path),
+                OverwritePolicy::Rename | OverwritePolicy::Overwrite => {}
+            }
+            
+            state.forget(track.number);
+            pending.push((track, path));
+        }
+        
+        pending
+    }
+
     pub fn transcode_all_tracks(
         &self,
         disc: &DiscId,
         album: &AlbumDetails,
         format: AudioFormat,
-    ) -> Result<(), Box<dyn Error>> {
-        std::fs::create_dir_all("output")?;
+        selection: &TrackSelection,
+    ) -> Result<RipReport> {
+        std::fs::create_dir_all(&self.output_dir)?;
         
         println!("Début du transcodage de l'album : {}", album.title);
         println!("Format d'encodage : {}", format.name());
-        println!("Nombre de pistes : {}", album.tracks.len());
+        println!("Pistes sélectionnées : {}", selection);
+        
+        let mut state = RipState::load_or_new(&self.output_dir, &disc.id(), &album.album_id, format.name())?;
+        state.selection = selection.clone();
+        state.save(&self.output_dir)?;
+        let mut report = RipReport::new();
         
-        for track in &album.tracks {
-            let filename = format!("output/{}", GStreamerEncoder::create_output_filename(track, album, format));
+        let pending = self.plan(album, format, selection, &mut state, &mut report);
+        let total = pending.len();
+        let mut remaining_ms: u64 = pending
+            .iter()
+            .map(|(track, _)| track.duration.unwrap_or(0) as u64)
+            .sum();
+        let mut encoding_time = Duration::ZERO;
+        let mut audio_done_ms: u64 = 0;
+        
+        for (index, (track, path)) in pending.into_iter().enumerate() {
+            if self.cancel.is_cancelled() {
+                report.cancelled = true;
+                break;
+            }
             
-            match self.encoder.transcode(disc, track, album, &filename, format) {
-                Ok(()) => {
-                    println!("✓ Piste {} transcodée avec succès", track.number);
+            let output_filename = path.to_string_lossy().to_string();
+            let track_ms = track.duration.unwrap_or(0) as u64;
+            remaining_ms = remaining_ms.saturating_sub(track_ms);
+            
+            self.emit(ProgressEvent::Started {
+                number: track.number,
+                title: track.title.clone(),
+                index,
+                total,
+            });
+            
+            let encode_started = Instant::now();
+            let mut on_progress = |position: ClockTime, duration: Option<ClockTime>| {
+                let position_ms = position.mseconds();
+                let duration_ms = duration.map(|d| d.mseconds()).unwrap_or(track_ms);
+                let fraction = if duration_ms > 0 {
+                    (position_ms as f64 / duration_ms as f64).min(1.0)
+                } else {
+                    0.0
+                };
+                
+                let elapsed_ms = (encoding_time + encode_started.elapsed()).as_millis() as u64;
+                let speed = if elapsed_ms > 0 {
+                    (audio_done_ms + position_ms) as f64 / elapsed_ms as f64
+                } else {
+                    0.0
+                };
+                let disc_eta = (speed > 0.0).then(|| {
+                    let left_ms = remaining_ms + duration_ms.saturating_sub(position_ms);
+                    Duration::from_millis((left_ms as f64 / speed) as u64)
+                });
+                
+                self.emit(ProgressEvent::Progress {
+                    fraction,
+                    speed,
+                    disc_eta,
+                });
+            };
+            
+            let job = TrackJob {
+                track,
+                album,
+                output_filename: &output_filename,
+                format,
+            };
+            let result = self.encoder.transcode(&job, &mut on_progress, &self.cancel);
+            encoding_time += encode_started.elapsed();
+            let (status, warnings) = match result {
+                Ok(warnings) => {
+                    match AudioChecksum::compute(&path, &self.cancel) {
+                        Ok(checksum) => {
+                            state.record(track.number, TrackState {
+                                filename: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                                crc32: checksum.crc32,
+                                duration_ms: checksum.duration_ms,
+                            });
+                            state.save(&self.output_dir)?;
+                            self.add_to_library(&path, track, album, format);
+                            (TrackStatus::Success, warnings)
+                        }
+                        Err(e) => (TrackStatus::Failed(e), warnings),
+                    }
                 }
-                Err(e) => {
-                    eprintln!("✗ Erreur lors du transcodage de la piste {}: {}", track.number, e);
+                Err(e) => (TrackStatus::Failed(e), Vec::new()),
+            };
+            audio_done_ms += track_ms;
+            
+            self.emit(ProgressEvent::Finished);
+            
+            match status {
+                TrackStatus::Failed(Error::Cancelled) => {
+                    println!("⏹ Transcodage de la piste {} annulé", track.number);
+                    report.cancelled = true;
                 }
+                TrackStatus::Success => println!("✓ Piste {} transcodée avec succès", track.number),
+                TrackStatus::Failed(ref e) => eprintln!("✗ Erreur lors du transcodage de la piste {}: {}", track.number, e),
+                TrackStatus::Skipped(_) => {}
             }
+            
+            report.push(TrackReport {
+                number: track.number,
+                title: track.title.clone(),
+                output_filename,
+                status,
+                warnings,
+            });
         }
         
-        Ok(())
+        if !report.cancelled {
+            match self.apply_replaygain(format, &report) {
+                Ok(()) => {}
+                Err(Error::Cancelled) => println!("⏹ Analyse ReplayGain annulée"),
+                Err(e) => eprintln!("⚠ Analyse ReplayGain impossible : {}", e),
+            }
+        }
+        
+        match self.write_album_playlist(album, &report) {
+            Ok(Some(path)) => println!("Liste de lecture de l'album : {}", path.display()),
+            Ok(None) => {}
+            Err(e) => eprintln!("⚠ Liste de lecture de l'album non écrite : {}", e),
+        }
+        
+        if let Some(ref library) = self.library
+            && let Err(e) = library.borrow().save()
+        {
+            eprintln!("⚠ Index de la bibliothèque non enregistré : {}", e);
+        }
+        
+        Ok(report)
     }
 }
@@ -0,0 +1,104 @@
+use crate::error::{Error, Result};
+use glib::{KeyFile, KeyFileFlags};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_GROUP: &str = "sync";
+const MANIFEST_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncEntry {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub source_modified: u64,
+    pub source_size: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncManifest {
+    entries: BTreeMap<PathBuf, SyncEntry>,
+}
+
+impl SyncManifest {
+    pub fn path_for(target_dir: &Path) -> PathBuf {
+        target_dir.join(format!(".{}.sync", env!("CARGO_PKG_NAME")))
+    }
+
+    pub fn load(target_dir: &Path) -> Result<Self> {
+        let mut manifest = Self::default();
+        let path = Self::path_for(target_dir);
+        if !path.is_file() {
+            return Ok(manifest);
+        }
+
+        let key_file = KeyFile::new();
+        key_file
+            .load_from_file(&path, KeyFileFlags::NONE)
+            .map_err(|e| Error::Sync(format!("{} : {}", path.display(), e)))?;
+
+        if key_file.uint64(MANIFEST_GROUP, "version").unwrap_or(0) != MANIFEST_VERSION {
+            return Err(Error::Sync(format!("{} : version non prise en charge", path.display())));
+        }
+
+        for group in key_file.groups().iter() {
+            if !group.starts_with("file ") {
+                continue;
+            }
+            let (Ok(source), Ok(target), Ok(source_modified), Ok(source_size), Ok(size)) = (
+                key_file.string(group, "source"),
+                key_file.string(group, "target"),
+                key_file.uint64(group, "source-modified"),
+                key_file.uint64(group, "source-size"),
+                key_file.uint64(group, "size"),
+            ) else {
+                continue;
+            };
+
+            manifest.insert(SyncEntry {
+                source: PathBuf::from(source.as_str()),
+                target: PathBuf::from(target.as_str()),
+                source_modified,
+                source_size,
+                size,
+            });
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn save(&self, target_dir: &Path) -> Result<()> {
+        let path = Self::path_for(target_dir);
+        let key_file = KeyFile::new();
+        key_file.set_uint64(MANIFEST_GROUP, "version", MANIFEST_VERSION);
+
+        for (i, entry) in self.entries.values().enumerate() {
+            let group = format!("file {}", i + 1);
+            key_file.set_string(&group, "source", &entry.source.to_string_lossy());
+            key_file.set_string(&group, "target", &entry.target.to_string_lossy());
+            key_file.set_uint64(&group, "source-modified", entry.source_modified);
+            key_file.set_uint64(&group, "source-size", entry.source_size);
+            key_file.set_uint64(&group, "size", entry.size);
+        }
+
+        key_file
+            .save_to_file(&path)
+            .map_err(|e| Error::Sync(format!("{} : {}", path.display(), e)))
+    }
+
+    pub fn get(&self, source: &Path) -> Option<&SyncEntry> {
+        self.entries.get(source)
+    }
+
+    pub fn insert(&mut self, entry: SyncEntry) {
+        self.entries.insert(entry.source.clone(), entry);
+    }
+
+    pub fn remove(&mut self, source: &Path) -> Option<SyncEntry> {
+        self.entries.remove(source)
+    }
+
+    pub fn sources(&self) -> Vec<PathBuf> {
+        self.entries.keys().cloned().collect()
+    }
+}
@@ -1,5 +1,19 @@
+pub mod conversion;
 pub mod gstreamer;
+pub mod library;
 pub mod manager;
+pub mod manifest;
+pub mod progress;
+pub mod report;
+pub mod state;
+pub mod sync;
 
-pub use gstreamer::{AudioEncoder, GStreamerEncoder};
+pub use conversion::ConversionOptions;
+pub use gstreamer::{AudioEncoder, GStreamerEncoder, TrackJob};
+pub use library::LibraryTranscoder;
 pub use manager::TranscodeManager;
+pub use manifest::{SyncEntry, SyncManifest};
+pub use progress::{ProgressCallback, ProgressEvent};
+pub use report::{RipReport, TrackReport, TrackStatus};
+pub use state::{OverwritePolicy, RipState, TrackState};
+pub use sync::DeviceSync;
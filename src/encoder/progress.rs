@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    Started {
+        number: u32,
+        title: String,
+        index: usize,
+        total: usize,
+    },
+    Progress {
+        fraction: f64,
+        speed: f64,
+        disc_eta: Option<Duration>,
+    },
+    Finished,
+}
+
+pub type ProgressCallback = Box<dyn Fn(&ProgressEvent)>;
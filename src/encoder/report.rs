@@ -0,0 +1,72 @@
+use crate::error::Error;
+
+#[derive(Debug)]
+pub enum TrackStatus {
+    Success,
+    Failed(Error),
+    Skipped(String),
+}
+
+#[derive(Debug)]
+pub struct TrackReport {
+    pub number: u32,
+    pub title: String,
+    pub output_filename: String,
+    pub status: TrackStatus,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct RipReport {
+    pub tracks: Vec<TrackReport>,
+    pub cancelled: bool,
+}
+
+impl RipReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, report: TrackReport) {
+        self.tracks.push(report);
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.tracks.iter().filter(|t| matches!(t.status, TrackStatus::Success)).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.tracks.iter().filter(|t| matches!(t.status, TrackStatus::Failed(_))).count()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.tracks.iter().filter(|t| matches!(t.status, TrackStatus::Skipped(_))).count()
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.failed() > 0
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n=== Rapport de transcodage ===");
+        for track in &self.tracks {
+            match &track.status {
+                TrackStatus::Success => println!("✓ {:02}. {}", track.number, track.title),
+                TrackStatus::Failed(e) => println!("✗ {:02}. {} : {}", track.number, track.title, e),
+                TrackStatus::Skipped(reason) => println!("- {:02}. {} (ignorée : {})", track.number, track.title, reason),
+            }
+            for warning in &track.warnings {
+                println!("    ⚠ {}", warning);
+            }
+        }
+        println!(
+            "\n{} réussie(s), {} échouée(s), {} ignorée(s)",
+            self.succeeded(),
+            self.failed(),
+            self.skipped()
+        );
+        if self.cancelled {
+            println!("Transcodage annulé avant la fin");
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::{Error, Result};
+use crate::utils::TrackSelection;
+use glib::{KeyFile, KeyFileFlags};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const RIP_GROUP: &str = "rip";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl OverwritePolicy {
+    pub fn name(&self) -> &str {
+        match self {
+            OverwritePolicy::Skip => "Conserver les fichiers existants et ne pas les réextraire",
+            OverwritePolicy::Overwrite => "Écraser les fichiers existants",
+            OverwritePolicy::Rename => "Conserver les fichiers existants et renommer les nouveaux",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "skip" | "ignorer" => Some(OverwritePolicy::Skip),
+            "overwrite" | "écraser" => Some(OverwritePolicy::Overwrite),
+            "rename" | "renommer" => Some(OverwritePolicy::Rename),
+            _ => None,
+        }
+    }
+
+    pub fn all_policies() -> Vec<OverwritePolicy> {
+        vec![
+            OverwritePolicy::Skip,
+            OverwritePolicy::Overwrite,
+            OverwritePolicy::Rename,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackState {
+    pub filename: String,
+    pub crc32: u32,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RipState {
+    pub disc_id: String,
+    pub album_id: String,
+    pub format: String,
+    pub selection: TrackSelection,
+    pub tracks: BTreeMap<u32, TrackState>,
+}
+
+impl RipState {
+    pub fn path_for(output_dir: &Path, disc_id: &str) -> PathBuf {
+        output_dir.join(format!(".{}.ripstate", disc_id))
+    }
+
+    pub fn exists(output_dir: &Path, disc_id: &str) -> bool {
+        Self::path_for(output_dir, disc_id).is_file()
+    }
+
+    pub fn new(disc_id: &str, album_id: &str, format: &str) -> Self {
+        Self {
+            disc_id: disc_id.to_string(),
+            album_id: album_id.to_string(),
+            format: format.to_string(),
+            selection: TrackSelection::all(),
+            tracks: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let key_file = KeyFile::new();
+        key_file
+            .load_from_file(path, KeyFileFlags::NONE)
+            .map_err(|e| Error::State(format!("{} : {}", path.display(), e)))?;
+
+        let string = |key: &str| key_file.string(RIP_GROUP, key).map(|s| s.to_string()).unwrap_or_default();
+        let mut state = Self::new(&string("disc-id"), &string("album-id"), &string("format"));
+        if let Ok(spec) = key_file.string(RIP_GROUP, "selection") {
+            state.selection = TrackSelection::parse(&spec).unwrap_or_default();
+        }
+
+        for group in key_file.groups().iter() {
+            let Some(number) = group.strip_prefix("track ").and_then(|n| n.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            let (Ok(filename), Ok(crc32), Ok(duration_ms)) = (
+                key_file.string(group, "filename"),
+                key_file.uint64(group, "crc32"),
+                key_file.uint64(group, "duration-ms"),
+            ) else {
+                continue;
+            };
+
+            state.tracks.insert(number, TrackState {
+                filename: filename.to_string(),
+                crc32: crc32 as u32,
+                duration_ms,
+            });
+        }
+
+        Ok(state)
+    }
+
+    pub fn load_or_new(output_dir: &Path, disc_id: &str, album_id: &str, format: &str) -> Result<Self> {
+        let path = Self::path_for(output_dir, disc_id);
+        if !path.is_file() {
+            return Ok(Self::new(disc_id, album_id, format));
+        }
+
+        let mut state = Self::load(&path)?;
+        if state.album_id != album_id || state.format != format {
+            println!("État d'extraction précédent ignoré (album ou format différent)");
+            return Ok(Self::new(disc_id, album_id, format));
+        }
+
+        state.disc_id = disc_id.to_string();
+        Ok(state)
+    }
+
+    pub fn find_in(directory: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "ripstate"))
+            .filter_map(|path| match Self::load(&path) {
+                Ok(state) => Some(state),
+                Err(e) => {
+                    eprintln!("⚠ {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn track_for_file(&self, filename: &str) -> Option<&TrackState> {
+        self.tracks.values().find(|track| track.filename == filename)
+    }
+
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let path = Self::path_for(output_dir, &self.disc_id);
+        let key_file = KeyFile::new();
+
+        key_file.set_string(RIP_GROUP, "disc-id", &self.disc_id);
+        key_file.set_string(RIP_GROUP, "album-id", &self.album_id);
+        key_file.set_string(RIP_GROUP, "format", &self.format);
+        key_file.set_string(RIP_GROUP, "selection", &self.selection.to_string());
+
+        for (number, track) in &self.tracks {
+            let group = format!("track {:02}", number);
+            key_file.set_string(&group, "filename", &track.filename);
+            key_file.set_uint64(&group, "crc32", track.crc32 as u64);
+            key_file.set_uint64(&group, "duration-ms", track.duration_ms);
+        }
+
+        key_file
+            .save_to_file(&path)
+            .map_err(|e| Error::State(format!("{} : {}", path.display(), e)))
+    }
+
+    pub fn completed(&self, number: u32) -> Option<&TrackState> {
+        self.tracks.get(&number)
+    }
+
+    pub fn record(&mut self, number: u32, track: TrackState) {
+        self.tracks.insert(number, track);
+    }
+
+    pub fn missing_tracks(&self, tracks: &[u32]) -> Option<TrackSelection> {
+        let missing: Vec<u32> = tracks
+            .iter()
+            .copied()
+            .filter(|n| !self.tracks.contains_key(n))
+            .collect();
+        TrackSelection::from_tracks(&missing)
+    }
+
+    pub fn forget(&mut self, number: u32) {
+        self.tracks.remove(&number);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::audio::{AudioFormat, EncoderProperty};
+use crate::cancel::CancellationToken;
+use crate::error::{Error, Result};
+use crate::library::LibraryTrack;
+use crate::utils::format_size;
+use super::{ConversionOptions, LibraryTranscoder, ProgressEvent, RipReport, SyncEntry, SyncManifest, TrackReport, TrackStatus};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyncAction {
+    Keep,
+    Copy,
+    Transcode,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub files: RipReport,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub over_budget: usize,
+    pub used_bytes: u64,
+}
+
+impl SyncReport {
+    pub fn print_summary(&self) {
+        if !self.files.tracks.is_empty() {
+            self.files.print_summary();
+        }
+        println!("\n=== Synchronisation ===");
+        println!("{} fichier(s) inchangé(s), {} supprimé(s)", self.unchanged, self.removed);
+        if self.over_budget > 0 {
+            println!("{} fichier(s) non copié(s) faute de place", self.over_budget);
+        }
+        println!("Espace occupé : {}", format_size(self.used_bytes));
+    }
+}
+
+pub struct DeviceSync {
+    transcoder: LibraryTranscoder,
+    target_dir: PathBuf,
+    format: AudioFormat,
+    budget: Option<u64>,
+    conversion: ConversionOptions,
+    cancel: CancellationToken,
+}
+
+impl DeviceSync {
+    pub fn new(source_root: impl Into<PathBuf>, target_dir: impl Into<PathBuf>, format: AudioFormat) -> Self {
+        let target_dir = target_dir.into();
+        Self {
+            transcoder: LibraryTranscoder::new(source_root, target_dir.clone(), format),
+            target_dir,
+            format,
+            budget: None,
+            conversion: ConversionOptions::default(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_budget(mut self, bytes: u64) -> Self {
+        self.budget = Some(bytes);
+        self
+    }
+
+    pub fn with_encoder_properties(mut self, properties: Vec<EncoderProperty>) -> Self {
+        self.transcoder = self.transcoder.with_encoder_properties(properties);
+        self
+    }
+
+    pub fn with_conversion(mut self, conversion: ConversionOptions) -> Self {
+        self.transcoder = self.transcoder.with_conversion(conversion);
+        self.conversion = conversion;
+        self
+    }
+
+    pub fn with_progress(mut self, callback: impl Fn(&ProgressEvent) + 'static) -> Self {
+        self.transcoder = self.transcoder.with_progress(callback);
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.transcoder = self.transcoder.with_cancellation(token.clone());
+        self.cancel = token;
+        self
+    }
+
+    fn is_compatible(&self, track: &LibraryTrack) -> bool {
+        let same_extension = track
+            .path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(self.format.file_extension()));
+        self.conversion.is_passthrough()
+            && same_extension
+            && track
+                .format
+                .as_deref()
+                .is_none_or(|codec| codec.contains(self.format.codec_name()))
+    }
+
+    fn target_for(&self, track: &LibraryTrack) -> PathBuf {
+        let relative = self.transcoder.relative_path(&track.path);
+        if self.is_compatible(track) {
+            relative
+        } else {
+            relative.with_extension(self.format.file_extension())
+        }
+    }
+
+    fn estimated_size(&self, track: &LibraryTrack) -> u64 {
+        let kbps: u64 = match self.format {
+            AudioFormat::Opus | AudioFormat::OpusMatroska => 96,
+            AudioFormat::Vorbis | AudioFormat::Aac => 128,
+            AudioFormat::Mp3 => 160,
+            AudioFormat::Wav | AudioFormat::Aiff => 1411,
+            AudioFormat::Flac | AudioFormat::Wavpack | AudioFormat::Alac | AudioFormat::FlacMatroska => {
+                return track.size;
+            }
+            AudioFormat::Custom(_) if self.format.is_lossless() => return track.size,
+            AudioFormat::Custom(_) => 128,
+        };
+        track.duration_ms.map(|ms| ms * kbps / 8).unwrap_or(track.size)
+    }
+
+    fn is_unchanged(&self, entry: &SyncEntry, track: &LibraryTrack, target: &Path) -> bool {
+        entry.source_modified == track.modified
+            && entry.source_size == track.size
+            && entry.target == target
+            && std::fs::metadata(self.target_dir.join(target)).is_ok_and(|m| m.len() == entry.size)
+    }
+
+    fn remove_target(&self, target: &Path) {
+        let path = self.target_dir.join(target);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                eprintln!("⚠ Impossible de supprimer {} : {}", path.display(), e);
+                return;
+            }
+        }
+
+        let mut directory = path.parent();
+        while let Some(dir) = directory.filter(|dir| *dir != self.target_dir.as_path() && dir.starts_with(&self.target_dir)) {
+            if std::fs::remove_dir(dir).is_err() {
+                break;
+            }
+            directory = dir.parent();
+        }
+    }
+
+    fn copy(&self, track: &LibraryTrack, target: &Path) -> Result<u64> {
+        let path = self.target_dir.join(target);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(std::fs::copy(&track.path, &path)?)
+    }
+
+    fn record(&self, manifest: &mut SyncManifest, track: &LibraryTrack, target: PathBuf, size: u64) {
+        let stale = manifest
+            .get(&track.path)
+            .map(|entry| entry.target.clone())
+            .filter(|previous| *previous != target);
+        if let Some(previous) = stale {
+            self.remove_target(&previous);
+        }
+        manifest.insert(SyncEntry {
+            source: track.path.clone(),
+            target,
+            source_modified: track.modified,
+            source_size: track.size,
+            size,
+        });
+    }
+
+    pub fn sync(&self, tracks: &[LibraryTrack]) -> Result<SyncReport> {
+        std::fs::create_dir_all(&self.target_dir)?;
+        let mut manifest = SyncManifest::load(&self.target_dir)?;
+        let mut report = SyncReport::default();
+
+        let mut planned_bytes = 0;
+        let mut plan = Vec::new();
+        for track in tracks {
+            let target = self.target_for(track);
+            let (action, size) = match manifest.get(&track.path) {
+                Some(entry) if self.is_unchanged(entry, track, &target) => (SyncAction::Keep, entry.size),
+                _ if self.is_compatible(track) => (SyncAction::Copy, track.size),
+                _ => (SyncAction::Transcode, self.estimated_size(track)),
+            };
+            if self.budget.is_some_and(|budget| planned_bytes + size > budget) {
+                report.over_budget += 1;
+                continue;
+            }
+            planned_bytes += size;
+            plan.push((track, target, action));
+        }
+
+        let selected: BTreeSet<&Path> = plan.iter().map(|(track, _, _)| track.path.as_path()).collect();
+        for source in manifest.sources() {
+            if selected.contains(source.as_path()) {
+                continue;
+            }
+            if let Some(entry) = manifest.remove(&source) {
+                self.remove_target(&entry.target);
+                report.removed += 1;
+            }
+        }
+
+        let mut pending = Vec::new();
+        for (track, target, action) in &plan {
+            match action {
+                SyncAction::Keep => report.unchanged += 1,
+                SyncAction::Transcode => pending.push((*track).clone()),
+                SyncAction::Copy => {
+                    if self.cancel.is_cancelled() {
+                        report.files.cancelled = true;
+                        break;
+                    }
+                    let status = match self.copy(track, target) {
+                        Ok(size) => {
+                            self.record(&mut manifest, track, target.clone(), size);
+                            println!("✓ Copié : {}", target.display());
+                            TrackStatus::Success
+                        }
+                        Err(e) => TrackStatus::Failed(e),
+                    };
+                    report.files.push(TrackReport {
+                        number: report.files.tracks.len() as u32 + 1,
+                        title: target.display().to_string(),
+                        output_filename: self.target_dir.join(target).to_string_lossy().to_string(),
+                        status,
+                        warnings: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        if !pending.is_empty() && !report.files.cancelled {
+            let offset = report.files.tracks.len() as u32;
+            let transcoded = match self.transcoder.transcode(&pending) {
+                Ok(transcoded) => transcoded,
+                Err(e) => {
+                    manifest.save(&self.target_dir)?;
+                    return Err(e);
+                }
+            };
+            for mut file in transcoded.tracks {
+                let track = &pending[file.number as usize - 1];
+                if matches!(file.status, TrackStatus::Success | TrackStatus::Skipped(_)) {
+                    match std::fs::metadata(&file.output_filename) {
+                        Ok(metadata) => self.record(&mut manifest, track, self.target_for(track), metadata.len()),
+                        Err(e) => file.status = TrackStatus::Failed(Error::from(e)),
+                    }
+                }
+                file.number += offset;
+                report.files.push(file);
+            }
+            report.files.cancelled = transcoded.cancelled;
+        }
+
+        report.used_bytes = plan
+            .iter()
+            .filter_map(|(track, _, _)| manifest.get(&track.path))
+            .map(|entry| entry.size)
+            .sum();
+        manifest.save(&self.target_dir)?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::metadata::MusicBrainzError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    NoDisc(String),
+    MissingPlugin { element: String },
+    NoSupportedFormat,
+    Metadata(MusicBrainzError),
+    Io(std::io::Error),
+    Pipeline(String),
+    Signal(String),
+    IncompleteRip { failed: usize },
+    State(String),
+    InvalidArgument(String),
+    Cancelled,
+    DBus(String),
+    Library(String),
+    Playlist(String),
+    Sync(String),
+    Incomplete { count: usize },
+    Damaged { count: usize },
+    Unreadable { count: usize },
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoDisc(reason) => write!(
+                f,
+                "Aucun CD audio lisible ({}). Assurez-vous qu'un CD audio est inséré dans le lecteur.",
+                reason
+            ),
+            Error::MissingPlugin { element } => write!(
+                f,
+                "Élément GStreamer '{}' introuvable. Installez le plugin GStreamer qui le fournit.",
+                element
+            ),
+            Error::NoSupportedFormat => write!(
+                f,
+                "Aucun format d'encodage supporté trouvé. Veuillez installer les plugins GStreamer nécessaires."
+            ),
+            Error::Metadata(e) => write!(f, "Erreur de métadonnées : {}", e),
+            Error::Io(e) => write!(f, "Erreur d'entrée/sortie : {}", e),
+            Error::Pipeline(message) => write!(f, "Erreur du pipeline GStreamer : {}", message),
+            Error::Signal(message) => write!(f, "Impossible d'installer le gestionnaire de signal : {}", message),
+            Error::IncompleteRip { failed } => write!(f, "{} piste(s) n'ont pas pu être transcodées", failed),
+            Error::State(message) => write!(f, "Fichier d'état d'extraction illisible : {}", message),
+            Error::InvalidArgument(message) => write!(f, "Argument invalide : {}", message),
+            Error::Cancelled => write!(f, "Opération annulée"),
+            Error::DBus(message) => write!(f, "Erreur D-Bus : {}", message),
+            Error::Library(message) => write!(f, "Index de la bibliothèque illisible : {}", message),
+            Error::Playlist(message) => write!(f, "Liste de lecture invalide : {}", message),
+            Error::Sync(message) => write!(f, "Manifeste de synchronisation illisible : {}", message),
+            Error::Incomplete { count } => write!(f, "{} fichier(s) n'ont pas pu être convertis ou copiés", count),
+            Error::Damaged { count } => write!(f, "{} fichier(s) endommagé(s), tronqué(s) ou illisible(s)", count),
+            Error::Unreadable { count } => write!(f, "{} fichier(s) introuvable(s) ou impossible(s) à analyser", count),
+            Error::Config(message) => write!(f, "Configuration invalide : {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Metadata(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<MusicBrainzError> for Error {
+    fn from(e: MusicBrainzError) -> Self {
+        Error::Metadata(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<discid::DiscError> for Error {
+    fn from(e: discid::DiscError) -> Self {
+        Error::NoDisc(e.to_string())
+    }
+}
+
+impl From<glib::Error> for Error {
+    fn from(e: glib::Error) -> Self {
+        Error::Pipeline(e.to_string())
+    }
+}
+
+impl From<glib::BoolError> for Error {
+    fn from(e: glib::BoolError) -> Self {
+        Error::Pipeline(e.to_string())
+    }
+}
+
+impl From<gstreamer::StateChangeError> for Error {
+    fn from(e: gstreamer::StateChangeError) -> Self {
+        Error::Pipeline(e.to_string())
+    }
+}
+
+impl From<gstreamer::PadLinkError> for Error {
+    fn from(e: gstreamer::PadLinkError) -> Self {
+        Error::Pipeline(e.to_string())
+    }
+}
+
+impl From<ctrlc::Error> for Error {
+    fn from(e: ctrlc::Error) -> Self {
+        Error::Signal(e.to_string())
+    }
+}
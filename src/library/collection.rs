@@ -0,0 +1,70 @@
+use super::LibraryTrack;
+
+#[derive(Debug, Clone, Default)]
+pub struct Library {
+    tracks: Vec<LibraryTrack>,
+}
+
+impl Library {
+    pub fn new(mut tracks: Vec<LibraryTrack>) -> Self {
+        tracks.sort_by(|a, b| {
+            a.artist_name()
+                .to_lowercase()
+                .cmp(&b.artist_name().to_lowercase())
+                .then_with(|| a.album_name().to_lowercase().cmp(&b.album_name().to_lowercase()))
+                .then_with(|| a.disc_number.cmp(&b.disc_number))
+                .then_with(|| a.track_number.cmp(&b.track_number))
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        Self { tracks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    pub fn tracks(&self) -> &[LibraryTrack] {
+        &self.tracks
+    }
+
+    pub fn artists(&self) -> Vec<&str> {
+        let mut artists: Vec<&str> = self.tracks.iter().map(|t| t.artist_name()).collect();
+        artists.dedup();
+        artists
+    }
+
+    pub fn albums(&self, artist: &str) -> Vec<&str> {
+        let mut albums: Vec<&str> = self
+            .tracks
+            .iter()
+            .filter(|t| t.artist_name() == artist)
+            .map(|t| t.album_name())
+            .collect();
+        albums.dedup();
+        albums
+    }
+
+    pub fn album_tracks(&self, artist: &str, album: &str) -> Vec<&LibraryTrack> {
+        self.tracks
+            .iter()
+            .filter(|t| t.artist_name() == artist && t.album_name() == album)
+            .collect()
+    }
+
+    pub fn search(&self, query: &str) -> Vec<&LibraryTrack> {
+        let query = query.to_lowercase();
+        self.tracks
+            .iter()
+            .filter(|track| {
+                [Some(&track.title), track.artist.as_ref(), track.album_artist.as_ref(), track.album.as_ref()]
+                    .into_iter()
+                    .flatten()
+                    .any(|field| field.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+}
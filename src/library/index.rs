@@ -0,0 +1,219 @@
+use super::{Library, LibraryScanner, LibraryTrack};
+use crate::error::{Error, Result};
+use glib::{KeyFile, KeyFileFlags};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const INDEX_GROUP: &str = "index";
+const INDEX_VERSION: u64 = 2;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IndexChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl IndexChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.updated == 0 && self.removed == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct LibraryIndex {
+    path: PathBuf,
+    tracks: BTreeMap<PathBuf, LibraryTrack>,
+}
+
+impl LibraryIndex {
+    pub fn default_path() -> PathBuf {
+        glib::user_cache_dir()
+            .join(env!("CARGO_PKG_NAME"))
+            .join("library.index")
+    }
+
+    pub fn open_default() -> Result<Self> {
+        Self::open(&Self::default_path())
+    }
+
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            tracks: BTreeMap::new(),
+        }
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut index = Self::new(path);
+        if !path.is_file() {
+            return Ok(index);
+        }
+
+        let key_file = KeyFile::new();
+        key_file
+            .load_from_file(path, KeyFileFlags::NONE)
+            .map_err(|e| Error::Library(format!("{} : {}", path.display(), e)))?;
+
+        if key_file.uint64(INDEX_GROUP, "version").unwrap_or(0) != INDEX_VERSION {
+            println!("Index de la bibliothèque obsolète, il sera reconstruit");
+            return Ok(index);
+        }
+
+        for group in key_file.groups().iter() {
+            if !group.starts_with("file ") {
+                continue;
+            }
+            let (Ok(uri), Ok(title)) = (key_file.string(group, "uri"), key_file.string(group, "title")) else {
+                continue;
+            };
+            let Ok((file_path, _)) = glib::filename_from_uri(&uri) else {
+                continue;
+            };
+
+            let string = |key: &str| key_file.string(group, key).ok().map(|s| s.to_string());
+            let number = |key: &str| key_file.uint64(group, key).ok();
+
+            let track = LibraryTrack {
+                path: file_path,
+                title: title.to_string(),
+                artist: string("artist"),
+                album_artist: string("album-artist"),
+                album: string("album"),
+                track_number: number("track-number").map(|n| n as u32),
+                disc_number: number("disc-number").map(|n| n as u32),
+                duration_ms: number("duration-ms"),
+                format: string("format"),
+                modified: number("modified").unwrap_or(0),
+                size: number("size").unwrap_or(0),
+            };
+            index.tracks.insert(track.path.clone(), track);
+        }
+
+        Ok(index)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let key_file = KeyFile::new();
+        key_file.set_uint64(INDEX_GROUP, "version", INDEX_VERSION);
+
+        for (i, track) in self.tracks.values().enumerate() {
+            let uri = glib::filename_to_uri(&track.path, None)
+                .map_err(|e| Error::Library(format!("{} : {}", track.path.display(), e)))?;
+            let group = format!("file {}", i + 1);
+            key_file.set_string(&group, "uri", &uri);
+            key_file.set_string(&group, "title", &track.title);
+
+            let strings = [
+                ("artist", &track.artist),
+                ("album-artist", &track.album_artist),
+                ("album", &track.album),
+                ("format", &track.format),
+            ];
+            for (key, value) in strings {
+                if let Some(value) = value {
+                    key_file.set_string(&group, key, value);
+                }
+            }
+
+            let numbers = [
+                ("track-number", track.track_number.map(u64::from)),
+                ("disc-number", track.disc_number.map(u64::from)),
+                ("duration-ms", track.duration_ms),
+                ("modified", Some(track.modified)),
+                ("size", Some(track.size)),
+            ];
+            for (key, value) in numbers {
+                if let Some(value) = value {
+                    key_file.set_uint64(&group, key, value);
+                }
+            }
+        }
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        key_file
+            .save_to_file(&temporary)
+            .map_err(|e| Error::Library(format!("{} : {}", temporary.display(), e)))?;
+        std::fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    pub fn update(&mut self, scanner: &LibraryScanner, root: &Path) -> Result<IndexChanges> {
+        let mut changes = IndexChanges::default();
+        let files = LibraryScanner::find_audio_files(root)?;
+
+        let stale: Vec<PathBuf> = self
+            .tracks
+            .keys()
+            .filter(|path| path.starts_with(root) && files.binary_search(path).is_err())
+            .cloned()
+            .collect();
+        for path in stale {
+            self.tracks.remove(&path);
+            changes.removed += 1;
+        }
+
+        let total = files.len();
+        for (i, path) in files.into_iter().enumerate() {
+            let known = self.tracks.get(&path).map(|track| track.is_up_to_date());
+            if known == Some(true) {
+                continue;
+            }
+
+            print!("\rAnalyse des fichiers : {}/{}", i + 1, total);
+            let _ = std::io::stdout().flush();
+
+            match scanner.read_track(path.clone()) {
+                Some(track) if known.is_some() => {
+                    changes.updated += 1;
+                    self.tracks.insert(path, track);
+                }
+                Some(track) => {
+                    changes.added += 1;
+                    self.tracks.insert(path, track);
+                }
+                None if known.is_some() => {
+                    changes.removed += 1;
+                    self.tracks.remove(&path);
+                }
+                None => {}
+            }
+        }
+        if !changes.is_empty() {
+            println!();
+        }
+
+        Ok(changes)
+    }
+
+    pub fn insert(&mut self, track: LibraryTrack) {
+        self.tracks.insert(track.path.clone(), track);
+    }
+
+    pub fn remove(&mut self, path: &Path) -> usize {
+        let before = self.tracks.len();
+        self.tracks.retain(|track_path, _| !track_path.starts_with(path));
+        before - self.tracks.len()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&LibraryTrack> {
+        self.tracks.get(path)
+    }
+
+    pub fn library(&self, root: Option<&Path>) -> Library {
+        let tracks = self
+            .tracks
+            .values()
+            .filter(|track| root.is_none_or(|root| track.path.starts_with(root)))
+            .cloned()
+            .collect();
+        Library::new(tracks)
+    }
+}
@@ -0,0 +1,11 @@
+pub mod collection;
+pub mod index;
+pub mod scanner;
+pub mod track;
+pub mod watcher;
+
+pub use collection::Library;
+pub use index::LibraryIndex;
+pub use scanner::LibraryScanner;
+pub use track::LibraryTrack;
+pub use watcher::LibraryWatcher;
@@ -0,0 +1,94 @@
+use super::LibraryTrack;
+use crate::error::Result;
+use gio::prelude::*;
+use gstreamer::ClockTime;
+use gstreamer_pbutils::Discoverer;
+use gstreamer_pbutils::prelude::*;
+use std::path::{Path, PathBuf};
+
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "opus", "ogg", "oga", "flac", "mp3", "m4a", "mp4", "aac", "wv", "wav", "aif", "aiff", "mka", "ape",
+];
+const DISCOVERY_TIMEOUT: ClockTime = ClockTime::from_seconds(10);
+
+pub struct LibraryScanner {
+    discoverer: Discoverer,
+}
+
+impl LibraryScanner {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            discoverer: Discoverer::new(DISCOVERY_TIMEOUT)?,
+        })
+    }
+
+    pub fn is_audio_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    pub fn find_audio_files(root: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        Self::collect_files(root, &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+
+    fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in std::fs::read_dir(directory)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                if let Err(e) = Self::collect_files(&path, files) {
+                    eprintln!("⚠ Dossier ignoré {} : {}", path.display(), e);
+                }
+            } else if path.is_file() && Self::is_audio_file(&path) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read_track(&self, path: PathBuf) -> Option<LibraryTrack> {
+        let uri = gio::File::for_path(&path).uri();
+        let mut track = LibraryTrack::from_path(path);
+
+        let info = match self.discoverer.discover_uri(&uri) {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("\n⚠ Impossible d'analyser {} : {}", track.path.display(), e);
+                return Some(track);
+            }
+        };
+
+        if info.audio_streams().is_empty() {
+            return None;
+        }
+
+        track.duration_ms = info.duration().map(|d| d.mseconds());
+        track.format = info.audio_streams()[0]
+            .caps()
+            .map(|caps| gstreamer_pbutils::pb_utils_get_codec_description(&caps).to_string());
+
+        if let Some(tags) = info.tags() {
+            if let Some(title) = tags.get::<gstreamer::tags::Title>() {
+                track.title = title.get().to_string();
+            }
+            track.artist = tags.get::<gstreamer::tags::Artist>().map(|t| t.get().to_string());
+            track.album_artist = tags.get::<gstreamer::tags::AlbumArtist>().map(|t| t.get().to_string());
+            track.album = tags.get::<gstreamer::tags::Album>().map(|t| t.get().to_string());
+            track.track_number = tags.get::<gstreamer::tags::TrackNumber>().map(|t| t.get());
+            track.disc_number = tags.get::<gstreamer::tags::AlbumVolumeNumber>().map(|t| t.get());
+        }
+
+        Some(track)
+    }
+}
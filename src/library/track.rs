@@ -0,0 +1,79 @@
+use crate::player::QueueItem;
+use crate::utils::format_time;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+const UNKNOWN_ARTIST: &str = "Artiste inconnu";
+const UNKNOWN_ALBUM: &str = "Album inconnu";
+
+#[derive(Debug, Clone)]
+pub struct LibraryTrack {
+    pub path: PathBuf,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub duration_ms: Option<u64>,
+    pub format: Option<String>,
+    pub modified: u64,
+    pub size: u64,
+}
+
+impl LibraryTrack {
+    pub fn from_path(path: PathBuf) -> Self {
+        let title = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let metadata = std::fs::metadata(&path).ok();
+        let modified = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            path,
+            title,
+            artist: None,
+            album_artist: None,
+            album: None,
+            track_number: None,
+            disc_number: None,
+            duration_ms: None,
+            format: None,
+            modified,
+            size: metadata.map(|m| m.len()).unwrap_or(0),
+        }
+    }
+
+    pub fn is_up_to_date(&self) -> bool {
+        let current = Self::from_path(self.path.clone());
+        current.modified == self.modified && current.size == self.size
+    }
+
+    pub fn artist_name(&self) -> &str {
+        self.album_artist
+            .as_deref()
+            .or(self.artist.as_deref())
+            .unwrap_or(UNKNOWN_ARTIST)
+    }
+
+    pub fn album_name(&self) -> &str {
+        self.album.as_deref().unwrap_or(UNKNOWN_ALBUM)
+    }
+
+    pub fn duration_string(&self) -> Option<String> {
+        self.duration_ms.map(|ms| format_time(ms / 1000))
+    }
+
+    pub fn queue_item(&self) -> QueueItem {
+        let mut item = QueueItem::file(self.path.to_string_lossy().to_string(), self.title.clone());
+        item.artist = self.artist.clone().or_else(|| self.album_artist.clone());
+        item.album = self.album.clone();
+        item.duration_ms = self.duration_ms;
+        item
+    }
+}
@@ -0,0 +1,127 @@
+use super::{LibraryIndex, LibraryScanner};
+use crate::error::Result;
+use gio::prelude::*;
+use gio::{Cancellable, FileMonitor, FileMonitorEvent, FileMonitorFlags};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+struct Inner {
+    index: Rc<RefCell<LibraryIndex>>,
+    scanner: LibraryScanner,
+    monitors: Vec<FileMonitor>,
+}
+
+pub struct LibraryWatcher {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl LibraryWatcher {
+    pub fn watch(index: Rc<RefCell<LibraryIndex>>, root: &Path) -> Result<Self> {
+        let watcher = Self {
+            inner: Rc::new(RefCell::new(Inner {
+                index,
+                scanner: LibraryScanner::new()?,
+                monitors: Vec::new(),
+            })),
+        };
+        Self::watch_tree(&watcher.inner, root)?;
+        Ok(watcher)
+    }
+
+    fn watch_tree(inner: &Rc<RefCell<Inner>>, directory: &Path) -> Result<()> {
+        Self::watch_directory(inner, directory)?;
+        for entry in std::fs::read_dir(directory)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+                Self::watch_tree(inner, &entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn watch_directory(inner: &Rc<RefCell<Inner>>, directory: &Path) -> Result<()> {
+        let monitor = gio::File::for_path(directory)
+            .monitor_directory(FileMonitorFlags::WATCH_MOVES, None::<&Cancellable>)?;
+
+        let weak = Rc::downgrade(inner);
+        monitor.connect_changed(move |_monitor, file, other_file, event| {
+            let Some(inner) = weak.upgrade() else {
+                return;
+            };
+            let (Some(path), other_path) = (file.path(), other_file.and_then(|f| f.path())) else {
+                return;
+            };
+            Self::on_changed(&inner, path, other_path, event);
+        });
+
+        inner.borrow_mut().monitors.push(monitor);
+        Ok(())
+    }
+
+    fn on_changed(inner: &Rc<RefCell<Inner>>, path: PathBuf, other_path: Option<PathBuf>, event: FileMonitorEvent) {
+        let changed = match event {
+            FileMonitorEvent::ChangesDoneHint | FileMonitorEvent::Created | FileMonitorEvent::MovedIn => {
+                Self::add(inner, &path)
+            }
+            FileMonitorEvent::Deleted | FileMonitorEvent::MovedOut => Self::remove(inner, &path),
+            FileMonitorEvent::Renamed => {
+                let removed = Self::remove(inner, &path);
+                let added = other_path.map(|other| Self::add(inner, &other)).unwrap_or(false);
+                removed || added
+            }
+            _ => false,
+        };
+
+        if changed
+            && let Err(e) = inner.borrow().index.borrow().save()
+        {
+            eprintln!("⚠ Index de la bibliothèque non enregistré : {}", e);
+        }
+    }
+
+    fn add(inner: &Rc<RefCell<Inner>>, path: &Path) -> bool {
+        if path.is_dir() {
+            if let Err(e) = Self::watch_tree(inner, path) {
+                eprintln!("⚠ Dossier non surveillé {} : {}", path.display(), e);
+            }
+            let state = inner.borrow();
+            let files = LibraryScanner::find_audio_files(path).unwrap_or_default();
+            let tracks: Vec<_> = files.into_iter().filter_map(|file| state.scanner.read_track(file)).collect();
+            let added = !tracks.is_empty();
+            let mut index = state.index.borrow_mut();
+            for track in tracks {
+                index.insert(track);
+            }
+            return added;
+        }
+
+        if !path.is_file() || !LibraryScanner::is_audio_file(path) {
+            return false;
+        }
+
+        let state = inner.borrow();
+        if state.index.borrow().get(path).is_some_and(|track| track.is_up_to_date()) {
+            return false;
+        }
+        match state.scanner.read_track(path.to_path_buf()) {
+            Some(track) => {
+                state.index.borrow_mut().insert(track);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove(inner: &Rc<RefCell<Inner>>, path: &Path) -> bool {
+        inner.borrow().index.borrow_mut().remove(path) > 0
+    }
+}
+
+impl Drop for LibraryWatcher {
+    fn drop(&mut self) {
+        for monitor in self.inner.borrow_mut().monitors.drain(..) {
+            monitor.cancel();
+        }
+    }
+}
@@ -1,29 +1,65 @@
 mod audio;
+mod cancel;
+mod cli;
+mod config;
+mod error;
 mod metadata;
+mod playlist;
 mod encoder;
+mod dbus;
+mod library;
 mod player;
 mod ui;
 mod utils;
 
+use audio::{AudioChecksum, CustomProfile, FormatValidator, IntegrityChecker, MediaInfo};
+use cancel::CancellationToken;
+use cli::{CliOptions, Mode};
+use config::Config;
+use dbus::{ControlService, MprisService};
 use discid::{DiscId, Features};
+use error::{Error, Result};
 use glib::MainLoop;
+use library::{LibraryIndex, LibraryScanner, LibraryTrack, LibraryWatcher};
 use metadata::{DiscDetails, MusicBrainzClient};
-use encoder::TranscodeManager;
-use player::{AudioPlayer, CdTrackPlayer, FilePlayer};
+use encoder::{DeviceSync, GStreamerEncoder, LibraryTranscoder, RipState, TranscodeManager};
+use player::{MediaSource, PlayQueue, QueueItem, QueuePlayer};
+use playlist::{Playlist, PlaylistFormat};
 use ui::UserInterface;
-use std::error::Error;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use utils::{CdDrive, TrackSelection};
 
-fn rip_cd_mode() -> Result<(), Box<dyn Error>> {
-    let disc = DiscId::read_features(None, Features::all())
-        .expect("Reading disc failed");
+fn select_cd_device(options: &CliOptions) -> Option<String> {
+    if options.device.is_some() {
+        return options.device.clone();
+    }
+    let drives = CdDrive::list();
+    (drives.len() > 1).then(|| UserInterface::select_drive(&drives))
+}
+
+fn rip_cd_mode(options: &CliOptions, config: &Config, cancel: &CancellationToken) -> Result<()> {
+    let device = select_cd_device(options);
+    if let Some(ref device) = device {
+        println!("Lecteur : {}", device);
+    }
+    let disc = DiscId::read_features(device.as_deref(), Features::all())?;
     
     DiscDetails::print_disc_info(&disc);
     
-    let audio_format = UserInterface::select_format();
+    let audio_format = UserInterface::select_format(&config.preferred_formats())?;
     
     println!("\n=== MusicBrainz Metadata ===");
-    match MusicBrainzClient::list_albums(&disc) {
-        Ok(albums) => {
+    if !config.uses_provider("musicbrainz") {
+        println!("Aucun fournisseur de métadonnées activé dans {}", config.path.display());
+        return Ok(());
+    }
+    let musicbrainz = MusicBrainzClient::new(&config.musicbrainz(options.contact.as_deref()))?;
+    match musicbrainz.list_albums(&disc) {
+        Ok(mut albums) => {
+            config.sort_by_country(&mut albums);
             if albums.is_empty() {
                 println!("No album metadata found");
             } else {
@@ -40,90 +76,369 @@ fn rip_cd_mode() -> Result<(), Box<dyn Error>> {
                     UserInterface::select_album(&albums)
                 };
                 
-                let manager = TranscodeManager::new();
-                manager.transcode_all_tracks(&disc, selected_album, audio_format)?;
+                ControlService::spawn_on_session_bus(cancel.clone());
+                
+                let mut manager = TranscodeManager::new()
+                    .with_output_dir(options.output.clone().unwrap_or_else(|| config.output_dir.clone()))
+                    .with_filename_template(&config.filename_template)
+                    .with_encoder(
+                        GStreamerEncoder::new()
+                            .with_properties(config.encoder_properties.clone())
+                            .with_device(device.clone()),
+                    )
+                    .with_progress(UserInterface::render_progress)
+                    .with_cancellation(cancel.clone());
+                match LibraryIndex::open_default() {
+                    Ok(index) => manager = manager.with_library_index(Rc::new(RefCell::new(index))),
+                    Err(e) => eprintln!("⚠ Index de la bibliothèque indisponible : {}", e),
+                }
+                let mut default_selection = TrackSelection::all();
+                if RipState::exists(manager.output_dir(), &disc.id()) {
+                    let state = RipState::load_or_new(manager.output_dir(), &disc.id(), &selected_album.album_id, audio_format.name())?;
+                    let numbers: Vec<u32> = selected_album
+                        .tracks
+                        .iter()
+                        .map(|t| t.number)
+                        .filter(|&number| state.selection.contains(number))
+                        .collect();
+                    if let Some(missing) = state.missing_tracks(&numbers) {
+                        println!("\nPistes restant à extraire : {}", missing);
+                        default_selection = missing;
+                    }
+                }
+                
+                let selection = match options.tracks {
+                    Some(ref selection) => selection.clone(),
+                    None => UserInterface::select_tracks(&default_selection),
+                };
+                let existing = manager.existing_outputs(selected_album, audio_format, &selection);
+                match options.overwrite {
+                    Some(policy) => manager = manager.with_overwrite_policy(policy),
+                    None if existing > 0 => {
+                        manager = manager.with_overwrite_policy(UserInterface::select_overwrite_policy(existing));
+                    }
+                    None => {}
+                }
+                
+                let report = manager.transcode_all_tracks(&disc, selected_album, audio_format, &selection)?;
+                report.print_summary();
+                
+                if report.cancelled {
+                    return Err(Error::Cancelled);
+                }
+                if report.has_failures() {
+                    return Err(Error::IncompleteRip { failed: report.failed() });
+                }
             }
         }
+        Err(e) => return Err(Error::Metadata(e)),
+    }
+    Ok(())
+}
+
+fn play_items(items: Vec<QueueItem>, options: &CliOptions, cancel: &CancellationToken) -> Result<()> {
+    let main_loop = MainLoop::new(None, false);
+    let player = QueuePlayer::new(PlayQueue::new(items), &main_loop)?;
+    if let Err(e) = player.set_replaygain(&options.replaygain) {
+        eprintln!("⚠ ReplayGain indisponible : {}", e);
+    }
+    if let Some(ref device) = options.device {
+        player.set_cd_device(device);
+    }
+    let _mpris = MprisService::on_session_bus(&player)
+        .map_err(|e| eprintln!("⚠ Contrôle MPRIS indisponible : {}", e))
+        .ok();
+    let ml_for_cancel = main_loop.clone();
+    let handler = cancel.connect(move || {
+        println!("⏹ Arrêt de la lecture...");
+        ml_for_cancel.quit();
+    });
+    match player.run() {
+        Ok(()) => println!("\n✓ Lecture terminée avec succès"),
+        Err(e) => eprintln!("\n❌ Erreur lors de la lecture : {}", e),
+    }
+    cancel.disconnect(handler);
+    cancel.reset();
+    Ok(())
+}
+
+fn play_playlist(path: &Path, options: &CliOptions, cancel: &CancellationToken) -> Result<()> {
+    let playlist = Playlist::load(path)?;
+    if playlist.is_empty() {
+        println!("❌ La liste de lecture '{}' est vide", path.display());
+        return Ok(());
+    }
+    
+    println!("Liste de lecture : {}", playlist.title.as_deref().unwrap_or(&path.display().to_string()));
+    for (i, entry) in playlist.entries.iter().enumerate() {
+        println!("{}. {}", i + 1, entry.display_title());
+    }
+    
+    play_items(playlist.queue_items(), options, cancel)
+}
+
+fn open_library(root: &Path) -> Result<Rc<RefCell<LibraryIndex>>> {
+    println!("Analyse de la bibliothèque '{}'...\n", root.display());
+    
+    let index = match LibraryIndex::open_default() {
+        Ok(index) => index,
         Err(e) => {
-            println!("Error fetching album metadata: {}", e);
+            eprintln!("⚠ Index de la bibliothèque illisible, reconstruction : {}", e);
+            LibraryIndex::new(&LibraryIndex::default_path())
+        }
+    };
+    let index = Rc::new(RefCell::new(index));
+    
+    let changes = index.borrow_mut().update(&LibraryScanner::new()?, root)?;
+    if !changes.is_empty() {
+        println!(
+            "Index mis à jour : {} ajoutée(s), {} modifiée(s), {} supprimée(s)",
+            changes.added, changes.updated, changes.removed
+        );
+        if let Err(e) = index.borrow().save() {
+            eprintln!("⚠ Index de la bibliothèque non enregistré : {}", e);
         }
     }
-    Ok(())
+    
+    Ok(index)
 }
 
-fn play_files_mode() -> Result<(), Box<dyn Error>> {
+fn play_files_mode(options: &CliOptions, config: &Config, cancel: &CancellationToken) -> Result<()> {
     println!("\n=== Lecteur de fichiers audio ===");
     
-    let directory = UserInterface::select_directory();
-    println!("Recherche de fichiers dans le dossier '{}'...\n", directory);
+    let directory = UserInterface::select_directory(&config.output_dir.to_string_lossy());
+    if PlaylistFormat::from_path(Path::new(&directory)).is_some() && Path::new(&directory).is_file() {
+        return play_playlist(Path::new(&directory), options, cancel);
+    }
     
-    let files = UserInterface::list_audio_files(&directory)?;
+    let root = std::fs::canonicalize(&directory)?;
+    let index = open_library(&root)?;
     
-    if files.is_empty() {
-        println!("❌ Aucun fichier audio trouvé dans le dossier '{}'", directory);
+    let _watcher = LibraryWatcher::watch(index.clone(), &root)
+        .map_err(|e| eprintln!("⚠ Surveillance du dossier indisponible : {}", e))
+        .ok();
+    
+    loop {
+        let library = index.borrow().library(Some(&root));
+        if library.is_empty() {
+            println!("❌ Aucun fichier audio trouvé dans le dossier '{}'", directory);
+            return Ok(());
+        }
+        println!("{} piste(s) dans la bibliothèque", library.len());
+        
+        let Some(tracks) = UserInterface::browse_library(&library) else {
+            break;
+        };
+        let items = tracks.iter().map(|track| track.queue_item()).collect();
+        
+        play_items(items, options, cancel)?;
+        
+        if !UserInterface::ask_continue("Lire d'autres fichiers ?") {
+            break;
+        }
+    }
+    
+    println!("Au revoir !");
+    Ok(())
+}
+
+fn select_library_tracks(options: &CliOptions, config: &Config) -> Result<Option<(PathBuf, Vec<LibraryTrack>)>> {
+    let source = UserInterface::prompt_path("Dossier source", &config.output_dir.to_string_lossy());
+    let root = std::fs::canonicalize(&source)?;
+    let library = open_library(&root)?.borrow().library(Some(&root));
+    
+    let query = options.query.clone().or_else(UserInterface::prompt_query);
+    let tracks: Vec<LibraryTrack> = match query {
+        Some(ref query) => library.search(query).into_iter().cloned().collect(),
+        None => library.tracks().to_vec(),
+    };
+    if tracks.is_empty() {
+        println!("❌ Aucun fichier audio sélectionné dans '{}'", root.display());
+        return Ok(None);
+    }
+    println!("{} fichier(s) sélectionné(s)", tracks.len());
+    Ok(Some((root, tracks)))
+}
+
+fn select_destination(options: &CliOptions, root: &Path, default: &str) -> Result<PathBuf> {
+    let output = match options.output {
+        Some(ref output) => output.clone(),
+        None => UserInterface::prompt_path("Dossier de destination", default),
+    };
+    std::fs::create_dir_all(&output)?;
+    if std::fs::canonicalize(&output)?.starts_with(root) {
+        return Err(Error::InvalidArgument("le dossier de destination ne peut pas être dans le dossier source".to_string()));
+    }
+    Ok(output)
+}
+
+fn transcode_library_mode(options: &CliOptions, config: &Config, cancel: &CancellationToken) -> Result<()> {
+    println!("\n=== Conversion de bibliothèque ===");
+    
+    let Some((root, tracks)) = select_library_tracks(options, config)? else {
         return Ok(());
+    };
+    let format = match options.format {
+        Some(format) => format,
+        None => UserInterface::select_format(&config.preferred_formats())?,
+    };
+    let output = select_destination(options, &root, &format!("converted/{}", format.file_extension()))?;
+    if !options.conversion.is_passthrough() {
+        println!("Conversion : {}", options.conversion.describe());
     }
     
-    let main_loop = MainLoop::new(None, false);
-    let ml_for_ctrlc = main_loop.clone();
+    let report = LibraryTranscoder::new(&root, &output, format)
+        .with_conversion(options.conversion)
+        .with_encoder_properties(config.encoder_properties.clone())
+        .with_progress(UserInterface::render_progress)
+        .with_cancellation(cancel.clone())
+        .transcode(&tracks)?;
+    report.print_summary();
     
-    ctrlc::set_handler(move || {
-        println!("\n⏹ Arrêt de la lecture...");
-        ml_for_ctrlc.quit();
-    })?;
+    if report.cancelled {
+        return Err(Error::Cancelled);
+    }
+    if report.has_failures() {
+        return Err(Error::Incomplete { count: report.failed() });
+    }
+    Ok(())
+}
+
+fn sync_device_mode(options: &CliOptions, config: &Config, cancel: &CancellationToken) -> Result<()> {
+    println!("\n=== Synchronisation d'un baladeur ===");
     
-    println!("Fichiers disponibles ({}) :", files.len());
-    for (i, file) in files.iter().enumerate() {
-        println!("{}. {}", i + 1, file);
+    let Some((root, tracks)) = select_library_tracks(options, config)? else {
+        return Ok(());
+    };
+    let format = match options.format {
+        Some(format) => format,
+        None => UserInterface::select_format(&config.preferred_formats())?,
+    };
+    let target = select_destination(options, &root, "device")?;
+    let budget = options.budget.or_else(UserInterface::prompt_budget);
+    
+    if !options.conversion.is_passthrough() {
+        println!("Conversion : {}", options.conversion.describe());
     }
     
-    loop {
-        if let Some(choice) = UserInterface::prompt_choice(
-            &format!("Choisissez un fichier à lire (1-{}, 0 pour quitter)", files.len()),
-            1,
-            files.len(),
-        ) {
-            let selected_file = &files[choice - 1];
-            let file_path = format!("{}/{}", directory, selected_file);
-            
-            let player = FilePlayer::new(file_path);
-            match player.play(&main_loop) {
-                Ok(()) => println!("\n✓ Lecture terminée avec succès"),
-                Err(e) => eprintln!("\n❌ Erreur lors de la lecture : {}", e),
-            }
-            
-            if !UserInterface::ask_continue("Lire un autre fichier ?") {
-                println!("Au revoir !");
-                return Ok(());
+    let mut sync = DeviceSync::new(&root, &target, format)
+        .with_conversion(options.conversion)
+        .with_encoder_properties(config.encoder_properties.clone())
+        .with_progress(UserInterface::render_progress)
+        .with_cancellation(cancel.clone());
+    if let Some(budget) = budget {
+        sync = sync.with_budget(budget);
+    }
+    
+    let report = sync.sync(&tracks)?;
+    report.print_summary();
+    
+    if report.files.cancelled {
+        return Err(Error::Cancelled);
+    }
+    if report.files.has_failures() {
+        return Err(Error::Incomplete { count: report.files.failed() });
+    }
+    Ok(())
+}
+
+fn verify_mode(config: &Config, cancel: &CancellationToken) -> Result<()> {
+    println!("\n=== Vérification de l'intégrité ===");
+    
+    let root = UserInterface::prompt_path("Dossier à vérifier", &config.output_dir.to_string_lossy());
+    let files = LibraryScanner::find_audio_files(&root)?;
+    if files.is_empty() {
+        println!("❌ Aucun fichier audio trouvé dans '{}'", root.display());
+        return Ok(());
+    }
+    
+    let mut states: BTreeMap<PathBuf, Vec<RipState>> = BTreeMap::new();
+    let mut damaged = 0;
+    for path in &files {
+        let directory = path.parent().unwrap_or(&root).to_path_buf();
+        let states = states
+            .entry(directory.clone())
+            .or_insert_with(|| RipState::find_in(&directory));
+        let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let expected = states
+            .iter()
+            .find_map(|state| state.track_for_file(&filename))
+            .map(|track| AudioChecksum {
+                crc32: track.crc32,
+                duration_ms: track.duration_ms,
+            });
+        
+        let check = IntegrityChecker::check(path, expected, cancel)?;
+        check.print();
+        if !check.is_intact() {
+            damaged += 1;
+        }
+    }
+    
+    println!("\n{} fichier(s) vérifié(s), {} intact(s), {} problème(s)", files.len(), files.len() - damaged, damaged);
+    if damaged > 0 {
+        return Err(Error::Damaged { count: damaged });
+    }
+    Ok(())
+}
+
+fn info_mode(options: &CliOptions, config: &Config) -> Result<()> {
+    let files = if options.files.is_empty() {
+        vec![UserInterface::prompt_path("Fichier à analyser", &config.output_dir.to_string_lossy())]
+    } else {
+        options.files.clone()
+    };
+    
+    let mut failed = 0;
+    for path in &files {
+        println!("\n{}", path.display());
+        let uri = match std::fs::canonicalize(path) {
+            Ok(path) => MediaSource::File(path.to_string_lossy().to_string()).uri(),
+            Err(e) => {
+                eprintln!("   ❌ {}", e);
+                failed += 1;
+                continue;
             }
-            
-            println!("\nFichiers disponibles ({}) :", files.len());
-            for (i, file) in files.iter().enumerate() {
-                println!("{}. {}", i + 1, file);
+        };
+        match MediaInfo::discover(&uri) {
+            Ok(info) => info.print(),
+            Err(e) => {
+                eprintln!("   ❌ {}", e);
+                failed += 1;
             }
-        } else {
-            println!("Au revoir !");
-            return Ok(());
         }
     }
+    
+    if failed > 0 {
+        return Err(Error::Unreadable { count: failed });
+    }
+    Ok(())
 }
 
-fn play_cd_mode() -> Result<(), Box<dyn Error>> {
+fn play_cd_mode(options: &CliOptions, config: &Config, cancel: &CancellationToken) -> Result<()> {
     println!("\n=== Lecteur de CD Audio ===");
     println!("Lecture des informations du disque...\n");
     
-    let disc = DiscId::read_features(None, Features::all())
-        .map_err(|e| {
-            eprintln!("❌ Erreur lors de la lecture du disque: {}", e);
-            eprintln!("Assurez-vous qu'un CD audio est inséré dans le lecteur.");
-            e
-        })?;
+    let options = &CliOptions {
+        device: select_cd_device(options),
+        ..options.clone()
+    };
+    if let Some(ref device) = options.device {
+        println!("Lecteur : {}", device);
+    }
+    let disc = DiscId::read_features(options.device.as_deref(), Features::all())?;
     
     DiscDetails::print_disc_info(&disc);
     
     println!("\n=== Récupération des métadonnées ===");
-    let albums = match MusicBrainzClient::list_albums(&disc) {
-        Ok(albums) if !albums.is_empty() => {
+    let lookup = if config.uses_provider("musicbrainz") {
+        MusicBrainzClient::new(&config.musicbrainz(options.contact.as_deref()))?.list_albums(&disc)
+    } else {
+        Ok(Vec::new())
+    };
+    let albums = match lookup {
+        Ok(mut albums) if !albums.is_empty() => {
+            config.sort_by_country(&mut albums);
             let album = if albums.len() == 1 {
                 &albums[0]
             } else {
@@ -162,39 +477,38 @@ fn play_cd_mode() -> Result<(), Box<dyn Error>> {
         }
     };
     
-    let main_loop = MainLoop::new(None, false);
-    let ml_for_ctrlc = main_loop.clone();
-    
-    ctrlc::set_handler(move || {
-        println!("\n⏹ Arrêt de la lecture...");
-        ml_for_ctrlc.quit();
-    })?;
-    
     let first_track = disc.first_track_num() as u32;
     let last_track = disc.last_track_num() as u32;
+    let mut cli_selection = options.tracks.clone();
     
     loop {
-        if let Some(choice) = UserInterface::prompt_choice(
-            &format!("Choisissez une piste à lire ({}-{}, 0 pour quitter)", first_track, last_track),
-            first_track as usize,
-            last_track as usize,
-        ) {
-            if let Some(ref album) = albums {
-                if let Some(track) = album.tracks.iter().find(|t| t.number == choice as u32) {
-                    println!("\n🎵 Piste {}: {}", choice, track.title);
-                    if let Some(ref artist) = track.artist {
-                        println!("   Artiste: {}", artist);
+        let selection = match cli_selection.take() {
+            Some(selection) => Some(selection),
+            None => UserInterface::prompt_tracks(&format!(
+                "Choisissez les pistes à lire ({}-{}, ex: 1-3,7,10- ou all, 0 pour quitter)",
+                first_track, last_track
+            )),
+        };
+        
+        if let Some(selection) = selection {
+            let items = selection
+                .tracks(first_track, last_track)
+                .into_iter()
+                .map(|number| {
+                    let item = QueueItem::cd_track(number, format!("Piste {}", number));
+                    let details = albums.as_ref().and_then(|album| {
+                        album.tracks.iter().find(|t| t.number == number).map(|track| (track, album))
+                    });
+                    match details {
+                        Some((track, album)) => item.with_track_details(track, album),
+                        None => item,
                     }
-                }
-            }
+                })
+                .collect();
             
-            let player = CdTrackPlayer::new(choice as u32);
-            match player.play(&main_loop) {
-                Ok(()) => println!("\n✓ Lecture terminée avec succès"),
-                Err(e) => eprintln!("\n❌ Erreur lors de la lecture : {}", e),
-            }
+            play_items(items, options, cancel)?;
             
-            if !UserInterface::ask_continue("Lire une autre piste ?") {
+            if !UserInterface::ask_continue("Lire d'autres pistes ?") {
                 println!("Au revoir !");
                 return Ok(());
             }
@@ -221,18 +535,81 @@ fn play_cd_mode() -> Result<(), Box<dyn Error>> {
     }
 }
 
+fn doctor_mode() -> Result<()> {
+    FormatValidator::print_environment();
+
+    println!("\n=== Lecteurs CD ===");
+    let drives = CdDrive::list();
+    if drives.is_empty() {
+        println!("✗ Aucun lecteur CD détecté");
+    }
+    for drive in &drives {
+        println!("• {}", drive.label());
+    }
+    println!("Lecteur par défaut : {}", CdDrive::default_device());
+
+    println!("\n=== Profils personnalisés ===");
+    println!("Fichier : {}", CustomProfile::profiles_path().display());
+    println!("Cibles GStreamer : {}", CustomProfile::targets_dir().display());
+    for profile in CustomProfile::load_all() {
+        FormatValidator::report_profile(&profile.create_encoding_profile(), &format!("{} ({})", profile.name, profile.id))
+            .print();
+    }
+    Ok(())
+}
+
 fn main() {
-    gstreamer::init().unwrap();
+    if let Err(e) = gstreamer::init() {
+        eprintln!("Erreur : impossible d'initialiser GStreamer : {}", e);
+        std::process::exit(1);
+    }
     let version = gstreamer::version_string();
     println!("{}", version);
+
+    let custom_profiles = CustomProfile::install();
+    if custom_profiles > 0 {
+        println!("{} profil(s) d'encodage personnalisé(s) chargé(s)", custom_profiles);
+    }
+    let config = Config::load_default();
+    
+    let mut options = match CliOptions::parse() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Erreur : {}", e);
+            CliOptions::print_usage();
+            std::process::exit(2);
+        }
+    };
+    
+    if options.device.is_none() {
+        options.device = config.device.clone();
+    }
+    
+    if options.help {
+        CliOptions::print_usage();
+        return;
+    }
     
-    let mode = UserInterface::select_mode();
+    let mode = options
+        .mode
+        .or_else(|| Mode::from_choice(UserInterface::select_mode()));
+    
+    let cancel = CancellationToken::new();
+    if let Err(e) = cancel.install_signal_handler() {
+        eprintln!("Erreur : {}", e);
+        std::process::exit(1);
+    }
     
     let result = match mode {
-        1 => rip_cd_mode(),
-        2 => play_files_mode(),
-        3 => play_cd_mode(),
-        _ => {
+        Some(Mode::Rip) => rip_cd_mode(&options, &config, &cancel),
+        Some(Mode::PlayFiles) => play_files_mode(&options, &config, &cancel),
+        Some(Mode::PlayCd) => play_cd_mode(&options, &config, &cancel),
+        Some(Mode::Transcode) => transcode_library_mode(&options, &config, &cancel),
+        Some(Mode::Sync) => sync_device_mode(&options, &config, &cancel),
+        Some(Mode::Verify) => verify_mode(&config, &cancel),
+        Some(Mode::Info) => info_mode(&options, &config),
+        Some(Mode::Doctor) => doctor_mode(),
+        None => {
             eprintln!("Mode invalide");
             std::process::exit(1);
         }
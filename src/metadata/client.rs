@@ -0,0 +1,147 @@
+use musicbrainz_rs::MusicBrainzClient as HttpClient;
+use musicbrainz_rs::error::Error as HttpError;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(16);
+pub const CONTACT_VARIABLE: &str = "HELLO_GTK_CONTACT";
+const DEFAULT_CONTACT: &str = "https://github.com/anrouxel/hello_gtk";
+
+#[derive(Debug, Clone)]
+pub struct MusicBrainzConfig {
+    pub app_name: String,
+    pub app_version: String,
+    pub contact: String,
+}
+
+impl Default for MusicBrainzConfig {
+    fn default() -> Self {
+        Self {
+            app_name: env!("CARGO_PKG_NAME").to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            contact: DEFAULT_CONTACT.to_string(),
+        }
+    }
+}
+
+impl MusicBrainzConfig {
+    pub fn user_agent(&self) -> String {
+        format!("{}/{} ( {} )", self.app_name, self.app_version, self.contact)
+    }
+}
+
+#[derive(Debug)]
+pub enum MusicBrainzError {
+    InvalidUserAgent(String),
+    NotFound { what: String },
+    Unavailable { what: String, attempts: u32 },
+    Request { what: String, message: String },
+}
+
+impl fmt::Display for MusicBrainzError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MusicBrainzError::InvalidUserAgent(agent) => {
+                write!(f, "User-Agent MusicBrainz invalide : {}", agent)
+            }
+            MusicBrainzError::NotFound { what } => {
+                write!(f, "{} introuvable sur MusicBrainz", what)
+            }
+            MusicBrainzError::Unavailable { what, attempts } => {
+                write!(f, "MusicBrainz indisponible pour {} après {} tentatives", what, attempts)
+            }
+            MusicBrainzError::Request { what, message } => {
+                write!(f, "Échec de la requête MusicBrainz pour {} : {}", what, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MusicBrainzError {}
+
+pub struct RateLimitedClient {
+    http: HttpClient,
+    last_request: Cell<Option<Instant>>,
+}
+
+impl RateLimitedClient {
+    pub fn new(config: &MusicBrainzConfig) -> Result<Self, MusicBrainzError> {
+        let user_agent = config.user_agent();
+        let mut http = HttpClient::default();
+        http.set_user_agent(&user_agent)
+            .map_err(|_| MusicBrainzError::InvalidUserAgent(user_agent.clone()))?;
+        http.max_retries = 1;
+
+        Ok(Self {
+            http,
+            last_request: Cell::new(None),
+        })
+    }
+
+    fn throttle(&self) {
+        if let Some(last) = self.last_request.get() {
+            let elapsed = last.elapsed();
+            if elapsed < MIN_REQUEST_INTERVAL {
+                thread::sleep(MIN_REQUEST_INTERVAL - elapsed);
+            }
+        }
+        self.last_request.set(Some(Instant::now()));
+    }
+
+    fn is_unavailable(error: &HttpError) -> bool {
+        match error {
+            HttpError::MaxRetriesExceeded => true,
+            HttpError::ReqwestError(e) => e.status().is_some_and(|status| status.as_u16() == 503),
+            _ => false,
+        }
+    }
+
+    fn send<T, F>(&self, request: &F) -> Result<T, HttpError>
+    where
+        F: Fn(&HttpClient) -> Result<T, HttpError>,
+    {
+        self.throttle();
+        panic::catch_unwind(AssertUnwindSafe(|| request(&self.http))).unwrap_or(Err(HttpError::MaxRetriesExceeded))
+    }
+
+    fn classify(what: &str, error: HttpError) -> MusicBrainzError {
+        match error {
+            HttpError::NotFound(_) => MusicBrainzError::NotFound { what: what.to_string() },
+            HttpError::ReqwestError(ref e) if e.status().is_some_and(|status| status.as_u16() == 404) => {
+                MusicBrainzError::NotFound { what: what.to_string() }
+            }
+            ref e if Self::is_unavailable(e) => MusicBrainzError::Unavailable {
+                what: what.to_string(),
+                attempts: MAX_ATTEMPTS,
+            },
+            other => MusicBrainzError::Request {
+                what: what.to_string(),
+                message: other.to_string(),
+            },
+        }
+    }
+
+    pub fn execute<T, F>(&self, what: &str, request: F) -> Result<T, MusicBrainzError>
+    where
+        F: Fn(&HttpClient) -> Result<T, HttpError>,
+    {
+        let mut attempt = 1;
+        let mut backoff = INITIAL_BACKOFF;
+        loop {
+            match self.send(&request) {
+                Err(ref e) if Self::is_unavailable(e) && attempt < MAX_ATTEMPTS => {
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    attempt += 1;
+                }
+                result => return result.map_err(|e| Self::classify(what, e)),
+            }
+        }
+    }
+}
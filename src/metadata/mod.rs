@@ -2,8 +2,10 @@ pub mod track;
 pub mod album;
 pub mod disc;
 pub mod musicbrainz;
+pub mod client;
 
 pub use track::TrackDetails;
 pub use album::AlbumDetails;
 pub use disc::DiscDetails;
 pub use musicbrainz::MusicBrainzClient;
+pub use client::{CONTACT_VARIABLE, MusicBrainzConfig, MusicBrainzError};
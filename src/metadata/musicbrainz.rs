@@ -1,4 +1,5 @@
 use super::{AlbumDetails, DiscDetails, TrackDetails};
+use super::client::{MusicBrainzConfig, MusicBrainzError, RateLimitedClient};
 use discid::DiscId;
 use musicbrainz_rs::{
     Fetch,
@@ -8,9 +9,17 @@ use musicbrainz_rs::{
     },
 };
 
-pub struct MusicBrainzClient;
+pub struct MusicBrainzClient {
+    client: RateLimitedClient,
+}
 
 impl MusicBrainzClient {
+    pub fn new(config: &MusicBrainzConfig) -> Result<Self, MusicBrainzError> {
+        Ok(Self {
+            client: RateLimitedClient::new(config)?,
+        })
+    }
+
     fn mcn_matches_barcode(mcn: Option<&str>, barcode: Option<&str>) -> bool {
         match (mcn, barcode) {
             (Some(mcn), Some(barcode)) => {
@@ -25,12 +34,14 @@ impl MusicBrainzClient {
         }
     }
 
-    pub fn query_disc(disc_details: &DiscDetails) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    pub fn query_disc(&self, disc_details: &DiscDetails) -> Result<Vec<String>, MusicBrainzError> {
         println!("Querying MusicBrainz for disc ID: {}", disc_details.id);
         
-        let result = MBDiscid::fetch()
-            .id(&disc_details.id)
-            .execute();
+        let result = self.client.execute(&format!("disc {}", disc_details.id), |http| {
+            MBDiscid::fetch()
+                .id(&disc_details.id)
+                .execute_with_client(http)
+        });
 
         match result {
             Ok(disc_result) => {
@@ -40,11 +51,11 @@ impl MusicBrainzClient {
                     println!("Found {} releases", releases.len());
                     
                     for release in releases {
-                        if let (Some(mcn), Some(barcode)) = (disc_details.mcn.as_deref(), release.barcode.as_deref()) {
-                            if Self::mcn_matches_barcode(Some(mcn), Some(barcode)) {
-                                println!("MCN matches barcode, using single release: {}", release.id);
-                                return Ok(vec![release.id]);
-                            }
+                        if let (Some(mcn), Some(barcode)) = (disc_details.mcn.as_deref(), release.barcode.as_deref())
+                            && Self::mcn_matches_barcode(Some(mcn), Some(barcode))
+                        {
+                            println!("MCN matches barcode, using single release: {}", release.id);
+                            return Ok(vec![release.id]);
                         }
                         release_ids.push(release.id);
                     }
@@ -52,10 +63,11 @@ impl MusicBrainzClient {
                 
                 Ok(release_ids)
             }
-            Err(e) => {
-                println!("No MusicBrainz metadata for discid {}: {}", disc_details.id, e);
+            Err(MusicBrainzError::NotFound { .. }) => {
+                println!("No MusicBrainz metadata for discid {}", disc_details.id);
                 Ok(Vec::new())
             }
+            Err(e) => Err(e),
         }
     }
 
@@ -97,74 +109,71 @@ impl MusicBrainzClient {
     }
 
     fn fill_tracks_from_release(release: &Release, album: &mut AlbumDetails) {
-        if let Some(ref media) = release.media {
-            for medium in media {
-                if let Some(ref tracks) = medium.tracks {
-                    album.tracks.clear();
-                    let mut track_offset = 0;
-                    let mut skip_data_tracks = true;
-
-                    for track in tracks {
-                        if skip_data_tracks && track.title == "[data track]" {
-                            continue;
-                        } else {
-                            skip_data_tracks = false;
-                            if album.tracks.is_empty() {
-                                track_offset = track.position.saturating_sub(1);
-                            }
-                        }
+        if let Some(medium) = release.media.as_ref().and_then(|media| media.first())
+            && let Some(ref tracks) = medium.tracks
+        {
+            album.tracks.clear();
+            let mut track_offset = 0;
+            let mut skip_data_tracks = true;
+
+            for track in tracks {
+                if skip_data_tracks && track.title == "[data track]" {
+                    continue;
+                } else {
+                    skip_data_tracks = false;
+                    if album.tracks.is_empty() {
+                        track_offset = track.position.saturating_sub(1);
+                    }
+                }
 
-                        let (track_artist, track_artist_sortname, track_artist_id) = 
-                            if let Some(ref artist_credit) = track.artist_credit {
-                                if !artist_credit.is_empty() {
-                                    let mut artist_name = String::new();
-                                    let mut first = true;
-                                    
-                                    for credit in artist_credit {
-                                        if !first {
-                                            artist_name.push_str(", ");
-                                        }
-                                        artist_name.push_str(&credit.name);
-                                        first = false;
-                                    }
-                                    
-                                    if artist_credit.len() == 1 {
-                                        let artist = &artist_credit[0];
-                                        (artist_name, Some(artist.artist.sort_name.clone()), Some(artist.artist.id.clone()))
-                                    } else {
-                                        (artist_name, None, None)
-                                    }
-                                } else {
-                                    (
-                                        album.artist.clone().unwrap_or_else(|| "Unknown Artist".to_string()),
-                                        album.artist_sortname.clone(),
-                                        album.artist_id.clone()
-                                    )
+                let (track_artist, track_artist_sortname, track_artist_id) = 
+                    if let Some(ref artist_credit) = track.artist_credit {
+                        if !artist_credit.is_empty() {
+                            let mut artist_name = String::new();
+                            let mut first = true;
+                            
+                            for credit in artist_credit {
+                                if !first {
+                                    artist_name.push_str(", ");
                                 }
+                                artist_name.push_str(&credit.name);
+                                first = false;
+                            }
+                            
+                            if artist_credit.len() == 1 {
+                                let artist = &artist_credit[0];
+                                (artist_name, Some(artist.artist.sort_name.clone()), Some(artist.artist.id.clone()))
                             } else {
-                                (
-                                    album.artist.clone().unwrap_or_else(|| "Unknown Artist".to_string()),
-                                    album.artist_sortname.clone(),
-                                    album.artist_id.clone()
-                                )
-                            };
-
-                        let track_details = TrackDetails {
-                            number: track.position - track_offset,
-                            title: track.title.clone(),
-                            duration: track.length,
-                            artist: Some(track_artist),
-                            artist_sortname: track_artist_sortname,
-                            artist_id: track_artist_id,
-                            track_id: track.recording.as_ref().map(|r| r.id.clone()),
-                            composer: None,
-                            composer_sortname: None,
-                        };
-
-                        album.tracks.push(track_details);
-                    }
-                }
-                break;
+                                (artist_name, None, None)
+                            }
+                        } else {
+                            (
+                                album.artist.clone().unwrap_or_else(|| "Unknown Artist".to_string()),
+                                album.artist_sortname.clone(),
+                                album.artist_id.clone()
+                            )
+                        }
+                    } else {
+                        (
+                            album.artist.clone().unwrap_or_else(|| "Unknown Artist".to_string()),
+                            album.artist_sortname.clone(),
+                            album.artist_id.clone()
+                        )
+                    };
+
+                let track_details = TrackDetails {
+                    number: track.position - track_offset,
+                    title: track.title.clone(),
+                    duration: track.length,
+                    artist: Some(track_artist),
+                    artist_sortname: track_artist_sortname,
+                    artist_id: track_artist_id,
+                    track_id: track.recording.as_ref().map(|r| r.id.clone()),
+                    composer: None,
+                    composer_sortname: None,
+                };
+
+                album.tracks.push(track_details);
             }
         }
     }
@@ -197,11 +206,11 @@ impl MusicBrainzClient {
         Some(album)
     }
 
-    pub fn list_albums(disc: &DiscId) -> Result<Vec<AlbumDetails>, Box<dyn std::error::Error>> {
+    pub fn list_albums(&self, disc: &DiscId) -> Result<Vec<AlbumDetails>, MusicBrainzError> {
         let mut disc_details = DiscDetails::from_disc(disc);
         let mut albums = Vec::new();
 
-        disc_details.release_ids = Self::query_disc(&disc_details)?;
+        disc_details.release_ids = self.query_disc(&disc_details)?;
 
         if disc_details.release_ids.is_empty() {
             println!("No releases found for this disc");
@@ -211,21 +220,17 @@ impl MusicBrainzClient {
         for release_id in &disc_details.release_ids {
             println!("Fetching release details for: {}", release_id);
             
-            match Release::fetch()
-                .id(release_id)
-                .with_artists()
-                .with_recordings()
-                .with_media()
-                .execute() 
-            {
-                Ok(release) => {
-                    if let Some(album) = Self::make_album_from_release(&release) {
-                        albums.push(album);
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to fetch release {}: {}", release_id, e);
-                }
+            let release = self.client.execute(&format!("release {}", release_id), |http| {
+                Release::fetch()
+                    .id(release_id)
+                    .with_artists()
+                    .with_recordings()
+                    .with_media()
+                    .execute_with_client(http)
+            })?;
+
+            if let Some(album) = Self::make_album_from_release(&release) {
+                albums.push(album);
             }
         }
 
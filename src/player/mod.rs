@@ -1,3 +1,7 @@
-pub mod player;
+pub mod queue;
+pub mod queue_player;
+pub mod replaygain;
 
-pub use player::{AudioPlayer, CdTrackPlayer, FilePlayer};
+pub use queue::{MediaSource, PlayQueue, QueueItem, RepeatMode};
+pub use queue_player::{ListenerId, PlaybackStatus, PlayerEvent, QueuePlayer};
+pub use replaygain::{ReplayGainMode, ReplayGainSettings};
@@ -0,0 +1,280 @@
+use crate::metadata::{AlbumDetails, TrackDetails};
+use gio::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+    Off,
+    One,
+    All,
+}
+
+impl RepeatMode {
+    pub fn name(&self) -> &str {
+        match self {
+            RepeatMode::Off => "désactivée",
+            RepeatMode::One => "piste",
+            RepeatMode::All => "tout",
+        }
+    }
+
+    pub fn cycle(&self) -> RepeatMode {
+        match self {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaSource {
+    File(String),
+    CdTrack(u32),
+    Uri(String),
+}
+
+impl MediaSource {
+    pub fn uri(&self) -> String {
+        match self {
+            MediaSource::File(path) => gio::File::for_path(path).uri().to_string(),
+            MediaSource::CdTrack(number) => format!("cdda://{}", number),
+            MediaSource::Uri(uri) => uri.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub source: MediaSource,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+impl QueueItem {
+    pub fn file(path: String, title: String) -> Self {
+        Self {
+            source: MediaSource::File(path),
+            title,
+            artist: None,
+            album: None,
+            duration_ms: None,
+        }
+    }
+
+    pub fn cd_track(number: u32, title: String) -> Self {
+        Self {
+            source: MediaSource::CdTrack(number),
+            title,
+            artist: None,
+            album: None,
+            duration_ms: None,
+        }
+    }
+
+    pub fn with_track_details(mut self, track: &TrackDetails, album: &AlbumDetails) -> Self {
+        self.title = track.title.clone();
+        self.artist = track.artist.clone().or_else(|| album.artist.clone());
+        self.album = Some(album.title.clone());
+        self.duration_ms = track.duration.map(u64::from);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayQueue {
+    items: Vec<QueueItem>,
+    order: Vec<usize>,
+    position: usize,
+    repeat: RepeatMode,
+    shuffle: bool,
+}
+
+impl PlayQueue {
+    pub fn new(items: Vec<QueueItem>) -> Self {
+        let order = (0..items.len()).collect();
+        Self {
+            items,
+            order,
+            position: 0,
+            repeat: RepeatMode::Off,
+            shuffle: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn items(&self) -> &[QueueItem] {
+        &self.items
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> Option<&QueueItem> {
+        self.order.get(self.position).map(|&i| &self.items[i])
+    }
+
+    pub fn repeat(&self) -> RepeatMode {
+        self.repeat
+    }
+
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        self.shuffle
+    }
+
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        let current = self.order.get(self.position).copied();
+        self.shuffle = shuffle;
+        self.order = (0..self.items.len()).collect();
+
+        if shuffle {
+            for i in (1..self.order.len()).rev() {
+                let j = glib::random_int_range(0, i as i32 + 1) as usize;
+                self.order.swap(i, j);
+            }
+        }
+
+        if let Some(current) = current
+            && let Some(index) = self.order.iter().position(|&i| i == current)
+        {
+            if shuffle {
+                self.order.swap(0, index);
+                self.position = 0;
+            } else {
+                self.position = index;
+            }
+        }
+    }
+
+    pub fn peek_after_current(&self) -> Option<usize> {
+        match self.repeat {
+            RepeatMode::One => Some(self.position),
+            _ if self.position + 1 < self.order.len() => Some(self.position + 1),
+            RepeatMode::All if !self.order.is_empty() => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn advance(&mut self) -> Option<&QueueItem> {
+        let next = self.peek_after_current()?;
+        self.position = next;
+        self.current()
+    }
+
+    pub fn next(&mut self) -> Option<&QueueItem> {
+        if self.position + 1 < self.order.len() {
+            self.position += 1;
+        } else if self.repeat != RepeatMode::Off && !self.order.is_empty() {
+            self.position = 0;
+        } else {
+            return None;
+        }
+        self.current()
+    }
+
+    pub fn previous(&mut self) -> Option<&QueueItem> {
+        if self.position > 0 {
+            self.position -= 1;
+        } else if self.repeat != RepeatMode::Off && !self.order.is_empty() {
+            self.position = self.order.len() - 1;
+        }
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayQueue, QueueItem, RepeatMode};
+
+    fn queue(len: usize) -> PlayQueue {
+        PlayQueue::new(
+            (1..=len)
+                .map(|n| QueueItem::file(format!("{:02}.flac", n), n.to_string()))
+                .collect(),
+        )
+    }
+
+    fn title(item: Option<&QueueItem>) -> Option<&str> {
+        item.map(|item| item.title.as_str())
+    }
+
+    #[test]
+    fn next_and_previous_stop_at_the_ends_without_repeat() {
+        let mut queue = queue(3);
+        assert_eq!(title(queue.current()), Some("1"));
+        assert_eq!(title(queue.previous()), Some("1"));
+        assert_eq!(title(queue.next()), Some("2"));
+        assert_eq!(title(queue.next()), Some("3"));
+        assert_eq!(title(queue.next()), None);
+        assert_eq!(queue.position(), 2);
+        assert_eq!(title(queue.advance()), None);
+        assert_eq!(title(queue.previous()), Some("2"));
+    }
+
+    #[test]
+    fn repeat_all_wraps_in_both_directions() {
+        let mut queue = queue(3);
+        queue.set_repeat(RepeatMode::All);
+        assert_eq!(title(queue.previous()), Some("3"));
+        assert_eq!(title(queue.next()), Some("1"));
+        queue.next();
+        queue.next();
+        assert_eq!(title(queue.advance()), Some("1"));
+    }
+
+    #[test]
+    fn repeat_one_replays_on_advance_but_skips_on_next() {
+        let mut queue = queue(3);
+        queue.set_repeat(RepeatMode::One);
+        assert_eq!(title(queue.advance()), Some("1"));
+        assert_eq!(title(queue.advance()), Some("1"));
+        assert_eq!(title(queue.next()), Some("2"));
+        queue.next();
+        assert_eq!(title(queue.advance()), Some("3"));
+        assert_eq!(title(queue.next()), Some("1"));
+        assert_eq!(title(queue.previous()), Some("3"));
+    }
+
+    #[test]
+    fn shuffle_keeps_the_current_track_and_every_item() {
+        let mut queue = queue(20);
+        queue.next();
+        queue.next();
+        queue.set_shuffle(true);
+        assert!(queue.is_shuffled());
+        assert_eq!(queue.position(), 0);
+        assert_eq!(title(queue.current()), Some("3"));
+
+        let mut seen = vec![queue.current().unwrap().title.clone()];
+        while let Some(item) = queue.next() {
+            seen.push(item.title.clone());
+        }
+        seen.sort_by_key(|title| title.parse::<u32>().unwrap());
+        assert_eq!(seen, (1..=20).map(|n| n.to_string()).collect::<Vec<_>>());
+
+        let current = queue.current().unwrap().title.clone();
+        queue.set_shuffle(false);
+        assert!(!queue.is_shuffled());
+        assert_eq!(queue.current().unwrap().title, current);
+        assert_eq!(queue.position() + 1, current.parse::<usize>().unwrap());
+    }
+
+    #[test]
+    fn empty_queue_has_no_current_item() {
+        let mut queue = queue(0);
+        queue.set_repeat(RepeatMode::All);
+        assert!(queue.current().is_none());
+        assert!(queue.next().is_none());
+        assert!(queue.previous().is_none());
+        assert!(queue.advance().is_none());
+    }
+}
@@ -0,0 +1,630 @@
+use super::{MediaSource, PlayQueue, QueueItem, ReplayGainMode, ReplayGainSettings, RepeatMode};
+use crate::audio::MediaInfo;
+use crate::audio::validator::CD_SOURCES;
+use crate::error::{Error, Result};
+use crate::playlist::Playlist;
+use crate::ui::UserInterface;
+use crate::utils::{format_time, make_element, parse_time};
+use glib::{ControlFlow, MainLoop};
+use gstreamer::{ClockTime, Element, MessageView, SeekFlags, State, bus::BusWatchGuard, prelude::*};
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const POSITION_DISPLAY_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_SEEK_SECONDS: i64 = 10;
+const VOLUME_STEP: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackStatus {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NowPlaying {
+    pub index: usize,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: Option<ClockTime>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlayerEvent {
+    TrackChanged,
+    StatusChanged,
+    OptionsChanged,
+    VolumeChanged,
+    Seeked(ClockTime),
+}
+
+type EventListener = Rc<dyn Fn(&QueuePlayer, PlayerEvent)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerId(u64);
+
+struct Shared {
+    queue: PlayQueue,
+    pending_advance: bool,
+}
+
+struct Inner {
+    playbin: Element,
+    shared: Arc<Mutex<Shared>>,
+    bus_watch: Option<BusWatchGuard>,
+    main_loop: MainLoop,
+    consecutive_failures: usize,
+    failed: bool,
+    now_playing: NowPlaying,
+    next_listener_id: u64,
+    listeners: Vec<(ListenerId, EventListener)>,
+    replaygain_volume: Option<Element>,
+}
+
+#[derive(Clone)]
+pub struct QueuePlayer {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl QueuePlayer {
+    pub fn new(queue: PlayQueue, ml: &MainLoop) -> Result<Self> {
+        let playbin = make_element("playbin3")?;
+        playbin.set_property_from_str("flags", "audio+soft-volume");
+
+        let shared = Arc::new(Mutex::new(Shared {
+            queue,
+            pending_advance: false,
+        }));
+
+        let shared_clone = shared.clone();
+        playbin.connect("about-to-finish", false, move |values| {
+            let Ok(playbin) = values[0].get::<Element>() else {
+                return None;
+            };
+            let Ok(mut shared) = shared_clone.lock() else {
+                return None;
+            };
+            if shared.pending_advance {
+                return None;
+            }
+            if let Some(uri) = shared.queue.advance().map(|item| item.source.uri()) {
+                playbin.set_property("uri", uri);
+                shared.pending_advance = true;
+            }
+            None
+        });
+
+        Ok(Self {
+            inner: Rc::new(RefCell::new(Inner {
+                playbin,
+                shared,
+                bus_watch: None,
+                main_loop: ml.clone(),
+                consecutive_failures: 0,
+                failed: false,
+                now_playing: NowPlaying::default(),
+                next_listener_id: 0,
+                listeners: Vec::new(),
+                replaygain_volume: None,
+            })),
+        })
+    }
+
+    pub fn set_cd_device(&self, device: &str) {
+        let device = device.to_string();
+        self.inner.borrow().playbin.connect("source-setup", false, move |values| {
+            let source = values[1].get::<Element>().ok()?;
+            let is_cd_source = source
+                .factory()
+                .is_some_and(|factory| CD_SOURCES.contains(&factory.name().as_str()));
+            if is_cd_source {
+                source.set_property("device", &device);
+            }
+            None
+        });
+    }
+
+    pub fn set_replaygain(&self, settings: &ReplayGainSettings) -> Result<()> {
+        let filter = settings.create_filter()?;
+        let mut state = self.inner.borrow_mut();
+        state.playbin.set_property("audio-filter", filter.as_ref().map(|f| f.bin.clone()));
+        state.replaygain_volume = filter.map(|f| f.volume);
+        Ok(())
+    }
+
+    pub fn replaygain_mode(&self) -> ReplayGainMode {
+        match self.inner.borrow().replaygain_volume {
+            Some(ref volume) if volume.property::<bool>("album-mode") => ReplayGainMode::Album,
+            Some(_) => ReplayGainMode::Track,
+            None => ReplayGainMode::Off,
+        }
+    }
+
+    pub fn toggle_replaygain_mode(&self) -> ReplayGainMode {
+        if let Some(ref volume) = self.inner.borrow().replaygain_volume {
+            let album_mode = volume.property::<bool>("album-mode");
+            volume.set_property("album-mode", !album_mode);
+        }
+        self.replaygain_mode()
+    }
+
+    pub fn connect_event<F: Fn(&QueuePlayer, PlayerEvent) + 'static>(&self, listener: F) -> ListenerId {
+        let mut state = self.inner.borrow_mut();
+        let id = ListenerId(state.next_listener_id);
+        state.next_listener_id += 1;
+        state.listeners.push((id, Rc::new(listener)));
+        id
+    }
+
+    pub fn disconnect_event(&self, id: ListenerId) {
+        self.inner.borrow_mut().listeners.retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    fn emit(inner: &Rc<RefCell<Inner>>, event: PlayerEvent) {
+        let listeners: Vec<EventListener> = inner
+            .borrow()
+            .listeners
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        let player = QueuePlayer { inner: inner.clone() };
+        for listener in listeners {
+            listener(&player, event);
+        }
+    }
+
+    fn current_item(inner: &Rc<RefCell<Inner>>) -> Option<(usize, usize, QueueItem)> {
+        let state = inner.borrow();
+        let shared = state.shared.lock().ok()?;
+        let item = shared.queue.current()?.clone();
+        Some((shared.queue.position(), shared.queue.len(), item))
+    }
+
+    fn load_current(inner: &Rc<RefCell<Inner>>) -> Result<()> {
+        let Some((_, _, item)) = Self::current_item(inner) else {
+            inner.borrow().main_loop.quit();
+            return Ok(());
+        };
+
+        let playbin = inner.borrow().playbin.clone();
+        playbin.set_state(State::Ready)?;
+        if let Some(bus) = playbin.bus() {
+            bus.set_flushing(true);
+            bus.set_flushing(false);
+        }
+        inner.borrow_mut().failed = false;
+        playbin.set_property("uri", item.source.uri());
+        playbin.set_state(State::Playing)?;
+        Ok(())
+    }
+
+    fn jump(inner: &Rc<RefCell<Inner>>, forward: bool) -> bool {
+        let moved = {
+            let state = inner.borrow();
+            let Ok(mut shared) = state.shared.lock() else {
+                return false;
+            };
+            let pending = std::mem::take(&mut shared.pending_advance);
+            if forward {
+                pending || shared.queue.next().is_some()
+            } else {
+                if pending {
+                    shared.queue.previous();
+                }
+                shared.queue.previous().is_some()
+            }
+        };
+
+        if moved
+            && let Err(e) = Self::load_current(inner)
+        {
+            eprintln!("\n❌ Erreur lors de la lecture : {}", e);
+        }
+        moved
+    }
+
+    fn on_error(inner: &Rc<RefCell<Inner>>) {
+        let give_up = {
+            let mut state = inner.borrow_mut();
+            state.consecutive_failures += 1;
+            let len = state.shared.lock().map(|s| s.queue.len()).unwrap_or(0);
+            state.consecutive_failures >= len
+        };
+
+        if give_up || !Self::jump(inner, true) {
+            inner.borrow().main_loop.quit();
+        }
+    }
+
+    fn on_stream_start(inner: &Rc<RefCell<Inner>>) {
+        if let Ok(mut shared) = inner.borrow().shared.lock() {
+            shared.pending_advance = false;
+        }
+        inner.borrow_mut().consecutive_failures = 0;
+
+        if let Some((position, len, item)) = Self::current_item(inner) {
+            match item.artist {
+                Some(ref artist) => println!("\n🎵 [{}/{}] {} - {}", position + 1, len, item.title, artist),
+                None => println!("\n🎵 [{}/{}] {}", position + 1, len, item.title),
+            }
+            if let MediaSource::File(_) = item.source {
+                match MediaInfo::discover(&item.source.uri()) {
+                    Ok(info) => println!("   {}", info.summary()),
+                    Err(e) => eprintln!("   ⚠ Informations techniques indisponibles : {}", e),
+                }
+            }
+            inner.borrow_mut().now_playing = NowPlaying {
+                index: position,
+                duration: item.duration_ms.map(ClockTime::from_mseconds),
+                title: item.title,
+                artist: item.artist,
+                album: item.album,
+            };
+        }
+        Self::emit(inner, PlayerEvent::TrackChanged);
+    }
+
+    fn on_tags(inner: &Rc<RefCell<Inner>>, tags: &gstreamer::TagListRef) {
+        let mut changed = false;
+        {
+            let mut state = inner.borrow_mut();
+            let now_playing = &mut state.now_playing;
+            if let Some(title) = tags.get::<gstreamer::tags::Title>() {
+                println!("   Titre: {}", title.get());
+                changed |= now_playing.title != title.get();
+                now_playing.title = title.get().to_string();
+            }
+            if let Some(artist) = tags.get::<gstreamer::tags::Artist>() {
+                println!("   Artiste: {}", artist.get());
+                changed |= now_playing.artist.as_deref() != Some(artist.get());
+                now_playing.artist = Some(artist.get().to_string());
+            }
+            if let Some(album) = tags.get::<gstreamer::tags::Album>() {
+                println!("   Album: {}", album.get());
+                changed |= now_playing.album.as_deref() != Some(album.get());
+                now_playing.album = Some(album.get().to_string());
+            }
+        }
+        if changed {
+            Self::emit(inner, PlayerEvent::TrackChanged);
+        }
+    }
+
+    fn watch_bus(inner: &Rc<RefCell<Inner>>) -> Result<BusWatchGuard> {
+        let playbin = inner.borrow().playbin.clone();
+        let bus = playbin.bus()
+            .ok_or_else(|| Error::Pipeline("Pipeline sans bus".to_string()))?;
+
+        let weak = Rc::downgrade(inner);
+        let guard = bus.add_watch_local(move |_bus, msg| {
+            let Some(inner) = weak.upgrade() else {
+                return ControlFlow::Break;
+            };
+
+            match msg.view() {
+                MessageView::StreamStart(_) => Self::on_stream_start(&inner),
+                MessageView::Eos(_) => {
+                    println!("\n✓ Fin de la file de lecture");
+                    inner.borrow().main_loop.quit();
+                }
+                MessageView::Error(err) => {
+                    eprintln!("\n❌ Erreur lors de la lecture: {} ({:?})", err.error(), err.debug());
+                    let already_failed = std::mem::replace(&mut inner.borrow_mut().failed, true);
+                    if !already_failed {
+                        glib::idle_add_local_once(move || Self::on_error(&inner));
+                    }
+                }
+                MessageView::Tag(tag_msg) => Self::on_tags(&inner, &tag_msg.tags()),
+                MessageView::StateChanged(change) => {
+                    let from_playbin = msg.src() == Some(inner.borrow().playbin.upcast_ref());
+                    if from_playbin && change.old() != change.current() {
+                        Self::emit(&inner, PlayerEvent::StatusChanged);
+                    }
+                }
+                _ => {}
+            }
+            ControlFlow::Continue
+        })?;
+
+        Ok(guard)
+    }
+
+    fn handle_command(inner: &Rc<RefCell<Inner>>, command: &str) {
+        match command {
+            "n" => {
+                if !Self::jump(inner, true) {
+                    println!("Fin de la file de lecture");
+                }
+            }
+            "p" => {
+                Self::jump(inner, false);
+            }
+            "r" => {
+                let player = QueuePlayer { inner: inner.clone() };
+                let repeat = player.repeat().cycle();
+                player.set_repeat(repeat);
+                println!("Répétition : {}", repeat.name());
+            }
+            "s" => {
+                let player = QueuePlayer { inner: inner.clone() };
+                let shuffle = !player.is_shuffled();
+                player.set_shuffle(shuffle);
+                println!("Lecture aléatoire : {}", if shuffle { "activée" } else { "désactivée" });
+            }
+            "q" => inner.borrow().main_loop.quit(),
+            other => {
+                let player = QueuePlayer { inner: inner.clone() };
+                match player.handle_control(other) {
+                    Ok(true) => {}
+                    Ok(false) => println!("Commande inconnue : {}", other),
+                    Err(e) => eprintln!("❌ {}", e),
+                }
+            }
+        }
+    }
+
+    fn playbin(&self) -> Element {
+        self.inner.borrow().playbin.clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.playbin().current_state() == State::Paused
+    }
+
+    pub fn status(&self) -> PlaybackStatus {
+        match self.playbin().current_state() {
+            State::Playing => PlaybackStatus::Playing,
+            State::Paused => PlaybackStatus::Paused,
+            _ => PlaybackStatus::Stopped,
+        }
+    }
+
+    pub fn now_playing(&self) -> NowPlaying {
+        let mut now_playing = self.inner.borrow().now_playing.clone();
+        if now_playing.duration.is_none() {
+            now_playing.duration = self.duration();
+        }
+        now_playing
+    }
+
+    pub fn has_next(&self) -> bool {
+        let state = self.inner.borrow();
+        let Ok(shared) = state.shared.lock() else {
+            return false;
+        };
+        shared.queue.position() + 1 < shared.queue.len() || shared.queue.repeat() != RepeatMode::Off
+    }
+
+    pub fn has_previous(&self) -> bool {
+        let state = self.inner.borrow();
+        let Ok(shared) = state.shared.lock() else {
+            return false;
+        };
+        shared.queue.position() > 0 || shared.queue.repeat() != RepeatMode::Off
+    }
+
+    pub fn queue_items(&self) -> Vec<QueueItem> {
+        let state = self.inner.borrow();
+        state.shared.lock().map(|s| s.queue.items().to_vec()).unwrap_or_default()
+    }
+
+    pub fn save_playlist(&self, path: &Path) -> Result<()> {
+        Playlist::from_queue_items(&self.queue_items()).save(path)
+    }
+
+    pub fn repeat(&self) -> RepeatMode {
+        let state = self.inner.borrow();
+        state.shared.lock().map(|s| s.queue.repeat()).unwrap_or(RepeatMode::Off)
+    }
+
+    pub fn set_repeat(&self, repeat: RepeatMode) {
+        if let Ok(mut shared) = self.inner.borrow().shared.lock() {
+            shared.queue.set_repeat(repeat);
+        }
+        Self::emit(&self.inner, PlayerEvent::OptionsChanged);
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        let state = self.inner.borrow();
+        state.shared.lock().map(|s| s.queue.is_shuffled()).unwrap_or(false)
+    }
+
+    pub fn set_shuffle(&self, shuffle: bool) {
+        if let Ok(mut shared) = self.inner.borrow().shared.lock() {
+            shared.queue.set_shuffle(shuffle);
+        }
+        Self::emit(&self.inner, PlayerEvent::OptionsChanged);
+    }
+
+    pub fn pause(&self) -> Result<()> {
+        self.playbin().set_state(State::Paused)?;
+        Ok(())
+    }
+
+    pub fn resume(&self) -> Result<()> {
+        self.playbin().set_state(State::Playing)?;
+        Ok(())
+    }
+
+    pub fn toggle_pause(&self) -> Result<()> {
+        if self.is_paused() {
+            println!("▶ Reprise");
+            self.resume()
+        } else {
+            println!("⏸ Pause");
+            self.pause()
+        }
+    }
+
+    pub fn position(&self) -> Option<ClockTime> {
+        self.playbin().query_position::<ClockTime>()
+    }
+
+    pub fn duration(&self) -> Option<ClockTime> {
+        self.playbin().query_duration::<ClockTime>()
+    }
+
+    pub fn seek_to(&self, position: ClockTime) -> Result<()> {
+        let position = match self.duration() {
+            Some(duration) => position.min(duration),
+            None => position,
+        };
+        self.playbin()
+            .seek_simple(SeekFlags::FLUSH | SeekFlags::ACCURATE, position)?;
+        Self::emit(&self.inner, PlayerEvent::Seeked(position));
+        Ok(())
+    }
+
+    pub fn seek_by(&self, offset_seconds: i64) -> Result<()> {
+        let current = self.position().unwrap_or(ClockTime::ZERO).seconds() as i64;
+        let target = (current + offset_seconds).max(0) as u64;
+        self.seek_to(ClockTime::from_seconds(target))
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.playbin().property::<f64>("volume")
+    }
+
+    pub fn set_volume(&self, volume: f64) {
+        self.playbin().set_property("volume", volume.clamp(0.0, 1.0));
+        Self::emit(&self.inner, PlayerEvent::VolumeChanged);
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.playbin().property::<bool>("mute")
+    }
+
+    pub fn set_muted(&self, muted: bool) {
+        self.playbin().set_property("mute", muted);
+        Self::emit(&self.inner, PlayerEvent::VolumeChanged);
+    }
+
+    pub fn next(&self) -> bool {
+        Self::jump(&self.inner, true)
+    }
+
+    pub fn previous(&self) -> bool {
+        Self::jump(&self.inner, false)
+    }
+
+    pub fn stop(&self) {
+        self.inner.borrow().main_loop.quit();
+    }
+
+    fn print_position(&self) {
+        let Some(position) = self.position() else {
+            return;
+        };
+        let duration = self
+            .duration()
+            .map(|d| format_time(d.seconds()))
+            .unwrap_or_else(|| "--:--".to_string());
+        let status = if self.is_paused() { "⏸" } else { "▶" };
+        let volume = if self.is_muted() {
+            "muet".to_string()
+        } else {
+            format!("{:.0}%", self.volume() * 100.0)
+        };
+        print!("\r{} {} / {} | volume {}   ", status, format_time(position.seconds()), duration, volume);
+        let _ = std::io::stdout().flush();
+    }
+
+    fn handle_control(&self, command: &str) -> Result<bool> {
+        match command {
+            "" => self.toggle_pause()?,
+            "g" => {
+                let mode = self.toggle_replaygain_mode();
+                println!("ReplayGain : {}", mode.name());
+            }
+            "m" => {
+                let muted = !self.is_muted();
+                self.set_muted(muted);
+                println!("Son {}", if muted { "coupé" } else { "rétabli" });
+            }
+            "i" => match Self::current_item(&self.inner) {
+                Some((_, _, item)) => {
+                    println!("{}", item.title);
+                    MediaInfo::discover(&item.source.uri())?.print();
+                }
+                None => println!("Aucune piste en cours"),
+            },
+            "v+" => self.set_volume(self.volume() + VOLUME_STEP),
+            "v-" => self.set_volume(self.volume() - VOLUME_STEP),
+            _ => {
+                if let Some(path) = command.strip_prefix("w ").map(str::trim).filter(|p| !p.is_empty()) {
+                    self.save_playlist(Path::new(path))?;
+                    println!("✓ Liste de lecture enregistrée : {}", path);
+                } else if let Some(percent) = command.strip_prefix("v ").and_then(|v| v.trim().parse::<f64>().ok()) {
+                    self.set_volume(percent / 100.0);
+                } else if let Some(timestamp) = command.strip_prefix('@') {
+                    match parse_time(timestamp) {
+                        Some(seconds) => self.seek_to(ClockTime::from_seconds(seconds))?,
+                        None => println!("Position invalide : {}", timestamp),
+                    }
+                } else if let Some(rest) = command.strip_prefix('+') {
+                    self.seek_by(rest.trim().parse().unwrap_or(DEFAULT_SEEK_SECONDS))?;
+                } else if let Some(rest) = command.strip_prefix('-') {
+                    self.seek_by(-rest.trim().parse().unwrap_or(DEFAULT_SEEK_SECONDS))?;
+                } else {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn print_help() {
+        println!("\nCommandes : n = suivante, p = précédente, r = répétition, s = aléatoire, q = arrêter");
+        println!("            Entrée = pause/reprise, +N/-N = avancer/reculer de N s, @m:ss = aller à");
+        println!("            v N = volume en %, v+/v- = volume ±10%, m = muet, g = ReplayGain piste/album");
+        println!("            i = informations techniques sur la piste en cours");
+        println!("            w fichier.m3u8|.pls|.xspf = enregistrer la file de lecture");
+    }
+
+    pub fn run(&self) -> Result<()> {
+        if Self::current_item(&self.inner).is_none() {
+            return Ok(());
+        }
+
+        Self::print_help();
+        let bus_watch = Self::watch_bus(&self.inner)?;
+        self.inner.borrow_mut().bus_watch = Some(bus_watch);
+        Self::load_current(&self.inner)?;
+
+        let weak = Rc::downgrade(&self.inner);
+        let command_source = glib::timeout_add_local(COMMAND_POLL_INTERVAL, move || {
+            let Some(inner) = weak.upgrade() else {
+                return ControlFlow::Break;
+            };
+            while let Some(line) = UserInterface::try_read_line() {
+                Self::handle_command(&inner, line.trim());
+            }
+            ControlFlow::Continue
+        });
+
+        let weak = Rc::downgrade(&self.inner);
+        let position_source = glib::timeout_add_local(POSITION_DISPLAY_INTERVAL, move || {
+            let Some(inner) = weak.upgrade() else {
+                return ControlFlow::Break;
+            };
+            QueuePlayer { inner }.print_position();
+            ControlFlow::Continue
+        });
+
+        let main_loop = self.inner.borrow().main_loop.clone();
+        main_loop.run();
+
+        command_source.remove();
+        position_source.remove();
+        let mut state = self.inner.borrow_mut();
+        state.bus_watch = None;
+        state.playbin.set_state(State::Null)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::{Error, Result};
+use crate::utils::make_element;
+use gstreamer::{Bin, Element, GhostPad, prelude::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayGainMode {
+    Off,
+    Track,
+    Album,
+}
+
+impl ReplayGainMode {
+    pub fn name(&self) -> &str {
+        match self {
+            ReplayGainMode::Off => "désactivé",
+            ReplayGainMode::Track => "piste",
+            ReplayGainMode::Album => "album",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "off" | "none" => Some(ReplayGainMode::Off),
+            "track" | "piste" => Some(ReplayGainMode::Track),
+            "album" => Some(ReplayGainMode::Album),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayGainSettings {
+    pub mode: ReplayGainMode,
+    pub pre_amp_db: f64,
+    pub prevent_clipping: bool,
+}
+
+impl Default for ReplayGainSettings {
+    fn default() -> Self {
+        Self {
+            mode: ReplayGainMode::Track,
+            pre_amp_db: 0.0,
+            prevent_clipping: true,
+        }
+    }
+}
+
+pub struct ReplayGainFilter {
+    pub bin: Element,
+    pub volume: Element,
+}
+
+impl ReplayGainSettings {
+    pub fn create_filter(&self) -> Result<Option<ReplayGainFilter>> {
+        if self.mode == ReplayGainMode::Off {
+            return Ok(None);
+        }
+
+        let bin = Bin::with_name("replaygain");
+        let convert_in = make_element("audioconvert")?;
+        let volume = make_element("rgvolume")?;
+        let limiter = make_element("rglimiter")?;
+        let convert_out = make_element("audioconvert")?;
+
+        volume.set_property("album-mode", self.mode == ReplayGainMode::Album);
+        volume.set_property("pre-amp", self.pre_amp_db.clamp(-60.0, 60.0));
+        volume.set_property("headroom", 0.0f64);
+        limiter.set_property("enabled", self.prevent_clipping);
+
+        bin.add_many([&convert_in, &volume, &limiter, &convert_out])?;
+        Element::link_many([&convert_in, &volume, &limiter, &convert_out])?;
+
+        for (element, name) in [(&convert_in, "sink"), (&convert_out, "src")] {
+            let pad = element
+                .static_pad(name)
+                .ok_or_else(|| Error::Pipeline(format!("pad {} introuvable sur audioconvert", name)))?;
+            bin.add_pad(&GhostPad::with_target(&pad)?)?;
+        }
+
+        Ok(Some(ReplayGainFilter {
+            bin: bin.upcast(),
+            volume,
+        }))
+    }
+}
@@ -0,0 +1,345 @@
+use super::{m3u, pls, xspf};
+use crate::error::{Error, Result};
+use crate::player::{MediaSource, QueueItem};
+use gio::prelude::*;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    M3u,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "m3u" | "m3u8" => Some(PlaylistFormat::M3u),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlaylistEntry {
+    pub source: MediaSource,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+impl PlaylistEntry {
+    pub fn new(source: MediaSource) -> Self {
+        Self {
+            source,
+            title: None,
+            artist: None,
+            album: None,
+            duration_ms: None,
+        }
+    }
+
+    pub fn from_location(location: &str, base_dir: &Path) -> Self {
+        let location = location.trim();
+        let source = if let Some(number) = location.strip_prefix("cdda://").and_then(|n| n.parse().ok()) {
+            MediaSource::CdTrack(number)
+        } else if location.contains("://") {
+            match gio::File::for_uri(location).path() {
+                Some(path) if location.starts_with("file:") => MediaSource::File(path.to_string_lossy().to_string()),
+                _ => MediaSource::Uri(location.to_string()),
+            }
+        } else {
+            MediaSource::File(base_dir.join(location).to_string_lossy().to_string())
+        };
+        Self::new(source)
+    }
+
+    pub fn from_queue_item(item: &QueueItem) -> Self {
+        Self {
+            source: item.source.clone(),
+            title: Some(item.title.clone()),
+            artist: item.artist.clone(),
+            album: item.album.clone(),
+            duration_ms: item.duration_ms,
+        }
+    }
+
+    pub fn path_for(&self, base_dir: &Path) -> String {
+        match self.source {
+            MediaSource::File(ref path) => {
+                let path = Self::absolute(Path::new(path));
+                match path.strip_prefix(Self::absolute(base_dir)) {
+                    Ok(relative) => relative.to_string_lossy().to_string(),
+                    Err(_) => path.to_string_lossy().to_string(),
+                }
+            }
+            ref source => source.uri(),
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self.source {
+            MediaSource::File(ref path) => gio::File::for_path(Self::absolute(Path::new(path))).uri().to_string(),
+            ref source => source.uri(),
+        }
+    }
+
+    pub fn display_title(&self) -> String {
+        if let Some(ref title) = self.title {
+            return title.clone();
+        }
+        match self.source {
+            MediaSource::File(ref path) => Path::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone()),
+            MediaSource::CdTrack(number) => format!("Piste {}", number),
+            MediaSource::Uri(ref uri) => uri.clone(),
+        }
+    }
+
+    pub fn queue_item(&self) -> QueueItem {
+        QueueItem {
+            source: self.source.clone(),
+            title: self.display_title(),
+            artist: self.artist.clone(),
+            album: self.album.clone(),
+            duration_ms: self.duration_ms,
+        }
+    }
+
+    fn absolute(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Playlist {
+    pub title: Option<String>,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl Playlist {
+    pub fn new(title: Option<String>) -> Self {
+        Self {
+            title,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn from_queue_items(items: &[QueueItem]) -> Self {
+        Self {
+            title: None,
+            entries: items.iter().map(PlaylistEntry::from_queue_item).collect(),
+        }
+    }
+
+    pub fn queue_items(&self) -> Vec<QueueItem> {
+        self.entries.iter().map(PlaylistEntry::queue_item).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn format_for(path: &Path) -> Result<PlaylistFormat> {
+        PlaylistFormat::from_path(path).ok_or_else(|| {
+            Error::InvalidArgument(format!("format de liste de lecture non reconnu : {}", path.display()))
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = Self::format_for(path)?;
+        let bytes = std::fs::read(path)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        match format {
+            PlaylistFormat::M3u => Ok(m3u::parse(&content, base_dir)),
+            PlaylistFormat::Pls => pls::parse(&content, base_dir),
+            PlaylistFormat::Xspf => xspf::parse(&content, base_dir),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let format = Self::format_for(path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        if !base_dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(base_dir)?;
+        }
+        let base_dir = if base_dir.as_os_str().is_empty() { Path::new(".") } else { base_dir };
+
+        let content = match format {
+            PlaylistFormat::M3u => m3u::write(self, base_dir),
+            PlaylistFormat::Pls => pls::write(self, base_dir),
+            PlaylistFormat::Xspf => xspf::write(self),
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{m3u, pls, xspf};
+    use crate::player::MediaSource;
+    use std::path::Path;
+
+    const BASE_DIR: &str = "/home/alice/Musique/Listes";
+
+    const VLC_M3U8: &str = "\u{feff}#EXTM3U
+#PLAYLIST:Soirée live
+#EXTINF:312,Pink Floyd - Shine On You Crazy Diamond - Live
+#EXTVLCOPT:start-time=12
+../Pink Floyd/Pulse/01 Shine On You Crazy Diamond.flac
+#EXTINF:-1,FIP
+http://icecast.radiofrance.fr/fip-hifi.aac
+#EXTINF:145,Édith Piaf - Non, je ne regrette rien
+file:///home/alice/Musique/%C3%89dith%20Piaf/Non%2C%20je%20ne%20regrette%20rien.ogg
+";
+
+    const WINAMP_PLS: &str = "[playlist]
+NumberOfEntries=3
+File1=../Miles Davis/Kind of Blue/01 - So What.flac
+Title1=Miles Davis - So What - Live at Newport
+Length1=545
+File2=http://ice1.somafm.com/groovesalad-128-mp3
+Title2=SomaFM: Groove Salad (#1): A nicely chilled plate of ambient/downtempo beats and grooves.
+Length2=-1
+File3=cdda://4
+Version=2
+";
+
+    const VLC_XSPF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist xmlns="http://xspf.org/ns/0/" xmlns:vlc="http://www.videolan.org/vlc/playlist/ns/0/" version="1">
+	<title>Rock &amp; Folk</title>
+	<trackList>
+		<track>
+			<location>file:///home/alice/Musique/Simon%20%26%20Garfunkel/The%20Boxer.flac</location>
+			<title>The Boxer - Live at Central Park</title>
+			<creator>Simon &amp; Garfunkel</creator>
+			<album>The Concert in Central Park</album>
+			<duration>308000</duration>
+			<extension application="http://www.videolan.org/vlc/playlist/0">
+				<vlc:id>0</vlc:id>
+			</extension>
+		</track>
+		<track>
+			<location>../Caf%C3%A9%20Tacvba/Re/Ingrata.mp3</location>
+			<title>Ingrata &#8211; versi&#243;n &lt;demo&gt;</title>
+			<creator>Café Tacvba</creator>
+			<duration>214000</duration>
+			<extension application="http://www.videolan.org/vlc/playlist/0">
+				<vlc:id>1</vlc:id>
+			</extension>
+		</track>
+	</trackList>
+	<extension application="http://www.videolan.org/vlc/playlist/0">
+		<vlc:item tid="0"/>
+		<vlc:item tid="1"/>
+	</extension>
+</playlist>
+"#;
+
+    fn file(path: &str) -> MediaSource {
+        MediaSource::File(path.to_string())
+    }
+
+    #[test]
+    fn parses_vlc_m3u8() {
+        let playlist = m3u::parse(VLC_M3U8, Path::new(BASE_DIR));
+        assert_eq!(playlist.title.as_deref(), Some("Soirée live"));
+        assert_eq!(playlist.entries.len(), 3);
+
+        let live = &playlist.entries[0];
+        assert_eq!(live.source, file("/home/alice/Musique/Listes/../Pink Floyd/Pulse/01 Shine On You Crazy Diamond.flac"));
+        assert_eq!(live.title.as_deref(), Some("Pink Floyd - Shine On You Crazy Diamond - Live"));
+        assert_eq!(live.artist, None);
+        assert_eq!(live.duration_ms, Some(312_000));
+
+        let radio = &playlist.entries[1];
+        assert_eq!(radio.source, MediaSource::Uri("http://icecast.radiofrance.fr/fip-hifi.aac".to_string()));
+        assert_eq!(radio.title.as_deref(), Some("FIP"));
+        assert_eq!(radio.duration_ms, None);
+
+        let piaf = &playlist.entries[2];
+        assert_eq!(piaf.source, file("/home/alice/Musique/Édith Piaf/Non, je ne regrette rien.ogg"));
+        assert_eq!(piaf.title.as_deref(), Some("Édith Piaf - Non, je ne regrette rien"));
+    }
+
+    #[test]
+    fn parses_winamp_pls() {
+        let playlist = pls::parse(WINAMP_PLS, Path::new(BASE_DIR)).unwrap();
+        assert_eq!(playlist.entries.len(), 3);
+
+        let live = &playlist.entries[0];
+        assert_eq!(live.source, file("/home/alice/Musique/Listes/../Miles Davis/Kind of Blue/01 - So What.flac"));
+        assert_eq!(live.title.as_deref(), Some("Miles Davis - So What - Live at Newport"));
+        assert_eq!(live.artist, None);
+        assert_eq!(live.duration_ms, Some(545_000));
+
+        let radio = &playlist.entries[1];
+        assert_eq!(radio.source, MediaSource::Uri("http://ice1.somafm.com/groovesalad-128-mp3".to_string()));
+        assert!(radio.title.as_deref().is_some_and(|title| title.starts_with("SomaFM: Groove Salad")));
+        assert_eq!(radio.duration_ms, None);
+
+        let cd = &playlist.entries[2];
+        assert_eq!(cd.source, MediaSource::CdTrack(4));
+        assert_eq!(cd.title, None);
+        assert_eq!(cd.display_title(), "Piste 4");
+    }
+
+    #[test]
+    fn parses_vlc_xspf() {
+        let playlist = xspf::parse(VLC_XSPF, Path::new(BASE_DIR)).unwrap();
+        assert_eq!(playlist.title.as_deref(), Some("Rock & Folk"));
+        assert_eq!(playlist.entries.len(), 2);
+
+        let boxer = &playlist.entries[0];
+        assert_eq!(boxer.source, file("/home/alice/Musique/Simon & Garfunkel/The Boxer.flac"));
+        assert_eq!(boxer.title.as_deref(), Some("The Boxer - Live at Central Park"));
+        assert_eq!(boxer.artist.as_deref(), Some("Simon & Garfunkel"));
+        assert_eq!(boxer.album.as_deref(), Some("The Concert in Central Park"));
+        assert_eq!(boxer.duration_ms, Some(308_000));
+
+        let ingrata = &playlist.entries[1];
+        assert_eq!(ingrata.source, file("/home/alice/Musique/Listes/../Café Tacvba/Re/Ingrata.mp3"));
+        assert_eq!(ingrata.title.as_deref(), Some("Ingrata – versión <demo>"));
+        assert_eq!(ingrata.artist.as_deref(), Some("Café Tacvba"));
+    }
+
+    #[test]
+    fn rewritten_m3u8_and_pls_keep_titles_and_relative_paths() {
+        let base_dir = Path::new(BASE_DIR);
+
+        let playlist = m3u::parse(VLC_M3U8, base_dir);
+        let written = m3u::write(&playlist, base_dir);
+        assert!(written.contains("#EXTINF:312,Pink Floyd - Shine On You Crazy Diamond - Live\n../Pink Floyd/"));
+        let reparsed = m3u::parse(&written, base_dir);
+        for (original, reparsed) in playlist.entries.iter().zip(&reparsed.entries) {
+            assert_eq!(original.source, reparsed.source);
+            assert_eq!(original.title, reparsed.title);
+            assert_eq!(original.duration_ms, reparsed.duration_ms);
+        }
+
+        let playlist = pls::parse(WINAMP_PLS, base_dir).unwrap();
+        let written = pls::write(&playlist, base_dir);
+        assert!(written.contains("Title1=Miles Davis - So What - Live at Newport\n"));
+        let reparsed = pls::parse(&written, base_dir).unwrap();
+        for (original, reparsed) in playlist.entries.iter().zip(&reparsed.entries) {
+            assert_eq!(original.source, reparsed.source);
+            assert_eq!(original.display_title(), reparsed.display_title());
+            assert_eq!(original.duration_ms, reparsed.duration_ms);
+        }
+    }
+
+    #[test]
+    fn rejects_files_without_a_playlist_section() {
+        assert!(pls::parse("[options]\nfoo=bar\n", Path::new(BASE_DIR)).is_err());
+        assert!(xspf::parse("<?xml version=\"1.0\"?>\n<rss/>", Path::new(BASE_DIR)).is_err());
+    }
+}
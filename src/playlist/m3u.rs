@@ -0,0 +1,57 @@
+use super::{Playlist, PlaylistEntry};
+use std::path::Path;
+
+pub fn parse(content: &str, base_dir: &Path) -> Playlist {
+    let mut playlist = Playlist::new(None);
+    let mut info: Option<(Option<u64>, Option<String>)> = None;
+
+    for line in content.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            let (duration, label) = extinf.split_once(',').unwrap_or((extinf, ""));
+            let duration_ms = duration
+                .split_whitespace()
+                .next()
+                .and_then(|d| d.parse::<i64>().ok())
+                .filter(|&d| d >= 0)
+                .map(|d| d as u64 * 1000);
+            let title = label.trim();
+            info = Some((duration_ms, (!title.is_empty()).then(|| title.to_string())));
+        } else if let Some(title) = line.strip_prefix("#PLAYLIST:") {
+            playlist.title = Some(title.trim().to_string());
+        } else if !line.starts_with('#') {
+            let mut entry = PlaylistEntry::from_location(line, base_dir);
+            if let Some((duration_ms, title)) = info.take() {
+                entry.duration_ms = duration_ms;
+                entry.title = title;
+            }
+            playlist.entries.push(entry);
+        }
+    }
+
+    playlist
+}
+
+pub fn write(playlist: &Playlist, base_dir: &Path) -> String {
+    let mut content = String::from("#EXTM3U\n");
+    if let Some(ref title) = playlist.title {
+        content.push_str(&format!("#PLAYLIST:{}\n", title));
+    }
+
+    for entry in &playlist.entries {
+        let duration = entry.duration_ms.map(|ms| (ms / 1000) as i64).unwrap_or(-1);
+        let label = match entry.artist {
+            Some(ref artist) => format!("{} - {}", artist, entry.display_title()),
+            None => entry.display_title(),
+        };
+        content.push_str(&format!("#EXTINF:{},{}\n", duration, label));
+        content.push_str(&entry.path_for(base_dir));
+        content.push('\n');
+    }
+
+    content
+}
@@ -0,0 +1,6 @@
+pub mod m3u;
+pub mod list;
+pub mod pls;
+pub mod xspf;
+
+pub use list::{Playlist, PlaylistEntry, PlaylistFormat};
@@ -0,0 +1,77 @@
+use super::{Playlist, PlaylistEntry};
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub fn parse(content: &str, base_dir: &Path) -> Result<Playlist> {
+    let mut in_playlist = false;
+    let mut files: BTreeMap<u32, PlaylistEntry> = BTreeMap::new();
+    let mut titles: BTreeMap<u32, String> = BTreeMap::new();
+    let mut lengths: BTreeMap<u32, u64> = BTreeMap::new();
+
+    for line in content.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_playlist = line.eq_ignore_ascii_case("[playlist]");
+            continue;
+        }
+        if !in_playlist {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+
+        let numbered = |prefix: &str| key.strip_prefix(prefix).and_then(|n| n.parse::<u32>().ok());
+        if let Some(n) = numbered("file") {
+            files.insert(n, PlaylistEntry::from_location(value, base_dir));
+        } else if let Some(n) = numbered("title") {
+            titles.insert(n, value.to_string());
+        } else if let Some(n) = numbered("length")
+            && let Ok(seconds) = value.parse::<i64>()
+            && seconds >= 0
+        {
+            lengths.insert(n, seconds as u64 * 1000);
+        }
+    }
+
+    if !in_playlist && files.is_empty() {
+        return Err(Error::Playlist("section [playlist] absente".to_string()));
+    }
+
+    let entries = files
+        .into_iter()
+        .map(|(n, mut entry)| {
+            entry.title = titles.remove(&n);
+            entry.duration_ms = lengths.remove(&n);
+            entry
+        })
+        .collect();
+
+    Ok(Playlist { title: None, entries })
+}
+
+pub fn write(playlist: &Playlist, base_dir: &Path) -> String {
+    let mut content = String::from("[playlist]\n");
+
+    for (i, entry) in playlist.entries.iter().enumerate() {
+        let n = i + 1;
+        content.push_str(&format!("File{}={}\n", n, entry.path_for(base_dir)));
+        let title = match entry.artist {
+            Some(ref artist) => format!("{} - {}", artist, entry.display_title()),
+            None => entry.display_title(),
+        };
+        content.push_str(&format!("Title{}={}\n", n, title));
+        let length = entry.duration_ms.map(|ms| (ms / 1000) as i64).unwrap_or(-1);
+        content.push_str(&format!("Length{}={}\n", n, length));
+    }
+
+    content.push_str(&format!("NumberOfEntries={}\nVersion=2\n", playlist.entries.len()));
+    content
+}
@@ -0,0 +1,150 @@
+use super::{Playlist, PlaylistEntry};
+use crate::error::{Error, Result};
+use std::path::Path;
+
+fn next_element<'a>(content: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut offset = 0;
+
+    loop {
+        let start = offset + content[offset..].find(&open)?;
+        let tag = &content[start + open.len()..];
+        let tag_end = tag.find('>')?;
+        let attributes = &tag[..tag_end];
+
+        if !attributes.chars().next().is_none_or(|c| c.is_whitespace() || c == '/') {
+            offset = start + open.len();
+            continue;
+        }
+        if attributes.ends_with('/') {
+            return Some(("", &tag[tag_end + 1..]));
+        }
+
+        let body = &tag[tag_end + 1..];
+        let end = body.find(&close)?;
+        return Some((&body[..end], &body[end + close.len()..]));
+    }
+}
+
+fn element<'a>(content: &'a str, name: &str) -> Option<&'a str> {
+    next_element(content, name).map(|(body, _)| body)
+}
+
+fn elements<'a>(content: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = content;
+    while let Some((body, after)) = next_element(rest, name) {
+        found.push(body);
+        rest = after;
+    }
+    found
+}
+
+fn text(content: &str, name: &str) -> Option<String> {
+    let body = element(content, name)?.trim();
+    let text = match body.strip_prefix("<![CDATA[").and_then(|t| t.strip_suffix("]]>")) {
+        Some(raw) => raw.to_string(),
+        None => unescape(body),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn unescape(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(';') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let entity = &after[..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(c) => decoded.push(c),
+            None => decoded.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn parse(content: &str, base_dir: &Path) -> Result<Playlist> {
+    let playlist_body = element(content, "playlist")
+        .ok_or_else(|| Error::Playlist("élément <playlist> absent".to_string()))?;
+    let track_list = element(playlist_body, "trackList").unwrap_or("");
+    let header = playlist_body.split("<trackList").next().unwrap_or("");
+
+    let mut playlist = Playlist::new(text(header, "title"));
+
+    for track in elements(track_list, "track") {
+        let Some(mut location) = text(track, "location") else {
+            continue;
+        };
+        if !location.contains("://")
+            && let Some(unescaped) = glib::Uri::unescape_string(&location, None)
+        {
+            location = unescaped.to_string();
+        }
+        let mut entry = PlaylistEntry::from_location(&location, base_dir);
+        entry.title = text(track, "title");
+        entry.artist = text(track, "creator");
+        entry.album = text(track, "album");
+        entry.duration_ms = text(track, "duration").and_then(|d| d.parse().ok());
+        playlist.entries.push(entry);
+    }
+
+    Ok(playlist)
+}
+
+pub fn write(playlist: &Playlist) -> String {
+    let mut content = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    content.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+    if let Some(ref title) = playlist.title {
+        content.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    }
+    content.push_str("  <trackList>\n");
+
+    for entry in &playlist.entries {
+        content.push_str("    <track>\n");
+        content.push_str(&format!("      <location>{}</location>\n", escape(&entry.uri())));
+        content.push_str(&format!("      <title>{}</title>\n", escape(&entry.display_title())));
+        if let Some(ref artist) = entry.artist {
+            content.push_str(&format!("      <creator>{}</creator>\n", escape(artist)));
+        }
+        if let Some(ref album) = entry.album {
+            content.push_str(&format!("      <album>{}</album>\n", escape(album)));
+        }
+        if let Some(duration_ms) = entry.duration_ms {
+            content.push_str(&format!("      <duration>{}</duration>\n", duration_ms));
+        }
+        content.push_str("    </track>\n");
+    }
+
+    content.push_str("  </trackList>\n</playlist>\n");
+    content
+}
@@ -1,22 +1,61 @@
 use crate::audio::{AudioFormat, FormatValidator};
+use crate::encoder::{OverwritePolicy, ProgressEvent};
+use crate::error::{Error, Result};
+use crate::library::{Library, LibraryTrack};
 use crate::metadata::AlbumDetails;
-use std::io::{self, Write};
+use crate::utils::{CdDrive, TrackSelection, format_time, parse_size};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+
+static INPUT: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
 
 pub struct UserInterface;
 
 impl UserInterface {
+    fn input() -> &'static Mutex<Receiver<String>> {
+        INPUT.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                for line in io::stdin().lock().lines() {
+                    let Ok(line) = line else { break };
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+            Mutex::new(receiver)
+        })
+    }
+
+    pub fn read_line() -> String {
+        Self::input()
+            .lock()
+            .ok()
+            .and_then(|receiver| receiver.recv().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn try_read_line() -> Option<String> {
+        Self::input().lock().ok()?.try_recv().ok()
+    }
+
     pub fn select_mode() -> u8 {
         println!("\n=== Mode de fonctionnement ===");
         println!("1. Ripper et transcoder un CD audio depuis un disque physique");
         println!("2. Lire des fichiers audio depuis un dossier");
         println!("3. Lire les pistes d'un CD audio directement");
+        println!("4. Convertir une bibliothèque de fichiers audio");
+        println!("5. Synchroniser un baladeur ou un dossier");
+        println!("6. Vérifier l'intégrité d'une bibliothèque");
         
         loop {
-            print!("\nChoisissez un mode (1-3) [défaut: 1]: ");
+            print!("\nChoisissez un mode (1-6) [défaut: 1]: ");
             io::stdout().flush().unwrap();
             
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
+            let input = Self::read_line();
             let input = input.trim();
             
             if input.is_empty() {
@@ -24,26 +63,25 @@ impl UserInterface {
             }
             
             match input.parse::<u8>() {
-                Ok(choice) if (1..=3).contains(&choice) => {
+                Ok(choice) if (1..=6).contains(&choice) => {
                     return choice;
                 }
                 _ => {
-                    println!("❌ Choix invalide. Veuillez entrer 1, 2 ou 3");
+                    println!("❌ Choix invalide. Veuillez entrer un nombre entre 1 et 6");
                 }
             }
         }
     }
 
-    pub fn select_format() -> AudioFormat {
+    pub fn select_format(preferred: &[AudioFormat]) -> Result<AudioFormat> {
         println!("\n=== Sélection du format d'encodage ===");
         println!("Vérification des formats supportés...");
         
-        let formats = FormatValidator::get_supported_formats();
+        let mut formats = FormatValidator::get_supported_formats();
+        formats.sort_by_key(|format| preferred.iter().position(|p| p == format).unwrap_or(preferred.len()));
         
         if formats.is_empty() {
-            eprintln!("❌ Aucun format d'encodage supporté trouvé !");
-            eprintln!("Veuillez installer les plugins GStreamer nécessaires.");
-            std::process::exit(1);
+            return Err(Error::NoSupportedFormat);
         }
         
         println!("\nFormats disponibles :");
@@ -56,20 +94,19 @@ impl UserInterface {
             print!("\nChoisissez un format (1-{}) [défaut: 1]: ", formats.len());
             io::stdout().flush().unwrap();
             
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
+            let input = Self::read_line();
             let input = input.trim();
             
             if input.is_empty() {
                 println!("Format sélectionné : {}", formats[0].name());
-                return formats[0];
+                return Ok(formats[0]);
             }
             
             match input.parse::<usize>() {
                 Ok(choice) if choice >= 1 && choice <= formats.len() => {
                     let selected = formats[choice - 1];
                     println!("Format sélectionné : {}", selected.name());
-                    return selected;
+                    return Ok(selected);
                 }
                 _ => {
                     println!("❌ Choix invalide. Veuillez entrer un nombre entre 1 et {}", formats.len());
@@ -96,8 +133,7 @@ impl UserInterface {
             print!("\nChoisissez un album (1-{}) [défaut: 1]: ", albums.len());
             io::stdout().flush().unwrap();
             
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
+            let input = Self::read_line();
             let input = input.trim();
             
             if input.is_empty() {
@@ -118,51 +154,249 @@ impl UserInterface {
         }
     }
 
-    pub fn select_directory() -> String {
+    pub fn select_overwrite_policy(existing: usize) -> OverwritePolicy {
+        let policies = OverwritePolicy::all_policies();
+        
+        println!("\n=== {} fichier(s) déjà présent(s) dans le dossier de destination ===", existing);
+        for (i, policy) in policies.iter().enumerate() {
+            println!("{}. {}", i + 1, policy.name());
+        }
+        
+        loop {
+            print!("\nQue faire des pistes existantes (1-{}) [défaut: 1]: ", policies.len());
+            io::stdout().flush().unwrap();
+            
+            let input = Self::read_line();
+            let input = input.trim();
+            
+            if input.is_empty() {
+                return policies[0];
+            }
+            
+            match input.parse::<usize>() {
+                Ok(choice) if choice >= 1 && choice <= policies.len() => {
+                    return policies[choice - 1];
+                }
+                _ => {
+                    println!("❌ Choix invalide. Veuillez entrer un nombre entre 1 et {}", policies.len());
+                }
+            }
+        }
+    }
+
+    pub fn select_tracks(default: &TrackSelection) -> TrackSelection {
+        loop {
+            print!("\nPistes à traiter (ex: 1-3,7,10-) [défaut: {}]: ", default);
+            io::stdout().flush().unwrap();
+            
+            let input = Self::read_line();
+            let input = input.trim();
+            
+            if input.is_empty() {
+                return default.clone();
+            }
+            
+            match TrackSelection::parse(input) {
+                Ok(selection) => return selection,
+                Err(e) => println!("❌ {}", e),
+            }
+        }
+    }
+
+    pub fn select_directory(default: &str) -> String {
         println!("\n=== Sélection du dossier ===");
-        print!("Entrez le chemin du dossier (ou appuyez sur Entrée pour 'output/'): ");
+        print!("Entrez le chemin du dossier ou d'une liste de lecture .m3u8/.pls/.xspf (ou appuyez sur Entrée pour '{}/'): ", default);
         io::stdout().flush().unwrap();
         
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
+        let input = Self::read_line();
         let input = input.trim();
         
         if input.is_empty() {
-            "output".to_string()
+            default.to_string()
         } else {
             input.to_string()
         }
     }
 
-    pub fn list_audio_files(directory: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let mut files = Vec::new();
-        let audio_extensions = vec!["opus", "ogg", "flac", "mp3", "m4a", "wv", "wav"];
+    pub fn prompt_path(prompt: &str, default: &str) -> PathBuf {
+        print!("\n{} [défaut: {}]: ", prompt, default);
+        io::stdout().flush().unwrap();
+        
+        let input = Self::read_line();
+        let input = input.trim();
+        
+        if input.is_empty() {
+            PathBuf::from(default)
+        } else {
+            PathBuf::from(input)
+        }
+    }
+
+    pub fn select_drive(drives: &[CdDrive]) -> String {
+        println!("\n=== Sélection du lecteur CD ===");
+        for (i, drive) in drives.iter().enumerate() {
+            println!("{}. {}", i + 1, drive.label());
+        }
+        
+        loop {
+            print!("\nChoisissez un lecteur (1-{}) [défaut: 1]: ", drives.len());
+            io::stdout().flush().unwrap();
+            
+            let input = Self::read_line();
+            let input = input.trim();
+            
+            if input.is_empty() {
+                return drives[0].device.clone();
+            }
+            
+            match input.parse::<usize>() {
+                Ok(choice) if choice >= 1 && choice <= drives.len() => {
+                    return drives[choice - 1].device.clone();
+                }
+                _ => {
+                    println!("❌ Choix invalide. Veuillez entrer un nombre entre 1 et {}", drives.len());
+                }
+            }
+        }
+    }
+
+    pub fn prompt_budget() -> Option<u64> {
+        loop {
+            print!("\nEspace disponible sur l'appareil (ex: 8G, 500M, vide pour illimité): ");
+            io::stdout().flush().unwrap();
+            
+            let input = Self::read_line();
+            let input = input.trim();
+            
+            if input.is_empty() {
+                return None;
+            }
+            match parse_size(input) {
+                Some(bytes) => return Some(bytes),
+                None => println!("❌ Taille invalide"),
+            }
+        }
+    }
+
+    pub fn prompt_query() -> Option<String> {
+        print!("\nFiltre de recherche (artiste, album ou titre, vide pour tout sélectionner): ");
+        io::stdout().flush().unwrap();
+        
+        let input = Self::read_line();
+        let input = input.trim();
         
-        for entry in std::fs::read_dir(directory)? {
-            let entry = entry?;
-            let path = entry.path();
+        (!input.is_empty()).then(|| input.to_string())
+    }
+
+    pub fn browse_library(library: &Library) -> Option<Vec<LibraryTrack>> {
+        loop {
+            let artists = library.artists();
+            println!("\n=== Artistes ({}) ===", artists.len());
+            for (i, artist) in artists.iter().enumerate() {
+                println!("{}. {}", i + 1, artist);
+            }
+
+            print!("\nChoisissez un artiste (1-{}, /texte pour rechercher, 0 pour quitter): ", artists.len());
+            io::stdout().flush().unwrap();
+            
+            let input = Self::read_line();
+            let input = input.trim();
             
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if audio_extensions.contains(&extension.to_str().unwrap_or("")) {
-                        if let Some(file_name) = path.file_name() {
-                            files.push(file_name.to_str().unwrap_or("").to_string());
-                        }
+            if let Some(query) = input.strip_prefix('/') {
+                if let Some(selected) = Self::select_search_results(library, query.trim()) {
+                    return Some(selected);
+                }
+                continue;
+            }
+            
+            if input.is_empty() || input == "0" {
+                return None;
+            }
+            
+            let artist = match input.parse::<usize>() {
+                Ok(choice) if choice >= 1 && choice <= artists.len() => artists[choice - 1],
+                _ => {
+                    println!("❌ Choix invalide");
+                    continue;
+                }
+            };
+
+            loop {
+                let albums = library.albums(artist);
+                println!("\n=== {} : albums ({}) ===", artist, albums.len());
+                for (i, album) in albums.iter().enumerate() {
+                    println!("{}. {} ({} pistes)", i + 1, album, library.album_tracks(artist, album).len());
+                }
+
+                let Some(choice) = Self::prompt_choice(
+                    &format!("Choisissez un album (1-{}, 0 pour revenir)", albums.len()),
+                    1,
+                    albums.len(),
+                ) else {
+                    break;
+                };
+                let album = albums[choice - 1];
+
+                let tracks = library.album_tracks(artist, album);
+                println!("\n=== {} - {} ===", artist, album);
+                for (i, track) in tracks.iter().enumerate() {
+                    print!("{}. {}", i + 1, track.title);
+                    if let Some(duration) = track.duration_string() {
+                        print!(" ({})", duration);
+                    }
+                    println!();
+                }
+
+                if let Some(selection) = Self::prompt_tracks(&format!(
+                    "Choisissez les pistes à lire (1-{}, ex: 1-3,7 ou all, 0 pour revenir)",
+                    tracks.len()
+                )) {
+                    let selected: Vec<LibraryTrack> = selection
+                        .tracks(1, tracks.len() as u32)
+                        .into_iter()
+                        .map(|n| tracks[n as usize - 1].clone())
+                        .collect();
+                    if !selected.is_empty() {
+                        return Some(selected);
                     }
                 }
             }
         }
+    }
+
+    fn select_search_results(library: &Library, query: &str) -> Option<Vec<LibraryTrack>> {
+        let results = library.search(query);
+        if results.is_empty() {
+            println!("Aucun résultat pour « {} »", query);
+            return None;
+        }
+        
+        println!("\n=== Résultats pour « {} » ({}) ===", query, results.len());
+        for (i, track) in results.iter().enumerate() {
+            print!("{}. {} - {} ({})", i + 1, track.title, track.artist_name(), track.album_name());
+            if let Some(duration) = track.duration_string() {
+                print!(" [{}]", duration);
+            }
+            println!();
+        }
         
-        files.sort();
-        Ok(files)
+        let selection = Self::prompt_tracks(&format!(
+            "Choisissez les pistes à lire (1-{}, ex: 1-3,7 ou all, 0 pour revenir)",
+            results.len()
+        ))?;
+        let selected: Vec<LibraryTrack> = selection
+            .tracks(1, results.len() as u32)
+            .into_iter()
+            .map(|n| results[n as usize - 1].clone())
+            .collect();
+        (!selected.is_empty()).then_some(selected)
     }
 
     pub fn ask_continue(message: &str) -> bool {
         print!("\n{} (o/N): ", message);
         io::stdout().flush().unwrap();
         
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
+        let input = Self::read_line();
         let input = input.trim().to_lowercase();
         
         matches!(input.as_str(), "o" | "oui" | "y" | "yes")
@@ -172,8 +406,7 @@ impl UserInterface {
         print!("\n{}: ", prompt);
         io::stdout().flush().unwrap();
         
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
+        let input = Self::read_line();
         let input = input.trim();
         
         match input.parse::<usize>() {
@@ -185,4 +418,53 @@ impl UserInterface {
             }
         }
     }
+
+    pub fn prompt_tracks(prompt: &str) -> Option<TrackSelection> {
+        print!("\n{}: ", prompt);
+        io::stdout().flush().unwrap();
+        
+        let input = Self::read_line();
+        let input = input.trim();
+        
+        if input.is_empty() || input == "0" {
+            return None;
+        }
+        
+        match TrackSelection::parse(input) {
+            Ok(selection) => Some(selection),
+            Err(e) => {
+                println!("❌ {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn render_progress(event: &ProgressEvent) {
+        const BAR_WIDTH: usize = 30;
+        
+        match event {
+            ProgressEvent::Started { number, title, index, total } => {
+                println!("\n[{}/{}] Piste {} : {}", index + 1, total, number, title);
+            }
+            ProgressEvent::Progress { fraction, speed, disc_eta } => {
+                let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+                let eta = match disc_eta {
+                    Some(eta) => format_time(eta.as_secs()),
+                    None => "--:--".to_string(),
+                };
+                print!(
+                    "\r[{}{}] {:>3.0}% | {:.1}x | reste (disque) {}  ",
+                    "#".repeat(filled),
+                    ".".repeat(BAR_WIDTH.saturating_sub(filled)),
+                    fraction * 100.0,
+                    speed,
+                    eta
+                );
+                io::stdout().flush().unwrap();
+            }
+            ProgressEvent::Finished => {
+                println!();
+            }
+        }
+    }
 }
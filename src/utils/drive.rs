@@ -0,0 +1,68 @@
+use discid::DiscId;
+use std::path::Path;
+
+const SYS_BLOCK: &str = "/sys/class/block";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CdDrive {
+    pub device: String,
+    pub vendor: Option<String>,
+    pub model: Option<String>,
+}
+
+impl CdDrive {
+    fn read_attribute(path: &Path) -> Option<String> {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn list() -> Vec<CdDrive> {
+        let mut drives: Vec<CdDrive> = std::fs::read_dir(SYS_BLOCK)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        if !name.starts_with("sr") {
+                            return None;
+                        }
+                        let device = entry.path().join("device");
+                        Some(CdDrive {
+                            device: format!("/dev/{}", name),
+                            vendor: Self::read_attribute(&device.join("vendor")),
+                            model: Self::read_attribute(&device.join("model")),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        drives.sort_by(|a, b| a.device.cmp(&b.device));
+
+        if drives.is_empty() {
+            let default = DiscId::default_device();
+            if Path::new(&default).exists() {
+                drives.push(CdDrive {
+                    device: default,
+                    vendor: None,
+                    model: None,
+                });
+            }
+        }
+        drives
+    }
+
+    pub fn default_device() -> String {
+        DiscId::default_device()
+    }
+
+    pub fn label(&self) -> String {
+        let name: Vec<&str> = [self.vendor.as_deref(), self.model.as_deref()].into_iter().flatten().collect();
+        if name.is_empty() {
+            self.device.clone()
+        } else {
+            format!("{} ({})", self.device, name.join(" "))
+        }
+    }
+}
@@ -1,3 +1,16 @@
+mod crc;
+mod drive;
+mod md5;
+mod selection;
+
+pub use crc::Crc32;
+pub use drive::CdDrive;
+pub use md5::Md5;
+pub use selection::TrackSelection;
+
+use crate::error::{Error, Result};
+use gstreamer::{Element, ElementFactory};
+
 pub fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| match c {
@@ -6,3 +19,113 @@ pub fn sanitize_filename(name: &str) -> String {
         })
         .collect()
 }
+
+pub fn format_time(seconds: u64) -> String {
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, (seconds % 3600) / 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+pub fn parse_time(input: &str) -> Option<u64> {
+    let mut seconds = 0u64;
+    let parts: Vec<&str> = input.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(seconds)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["o", "Ko", "Mo", "Go", "To"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+pub fn parse_size(input: &str) -> Option<u64> {
+    let input = input.trim().to_uppercase();
+    let input = input.trim_end_matches(['O', 'B']);
+    let (number, multiplier) = match input.char_indices().last()? {
+        (i, 'K') => (&input[..i], 1u64 << 10),
+        (i, 'M') => (&input[..i], 1 << 20),
+        (i, 'G') => (&input[..i], 1 << 30),
+        (i, 'T') => (&input[..i], 1 << 40),
+        _ => (input, 1),
+    };
+    let value = number.trim().replace(',', ".").parse::<f64>().ok()?;
+    (value >= 0.0).then_some((value * multiplier as f64) as u64)
+}
+
+pub fn make_element(factory: &str) -> Result<Element> {
+    ElementFactory::make(factory)
+        .build()
+        .map_err(|_| Error::MissingPlugin { element: factory.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, format_time, parse_size, parse_time};
+
+    #[test]
+    fn formats_minutes_and_hours() {
+        assert_eq!(format_time(0), "0:00");
+        assert_eq!(format_time(65), "1:05");
+        assert_eq!(format_time(3599), "59:59");
+        assert_eq!(format_time(3600), "1:00:00");
+        assert_eq!(format_time(3725), "1:02:05");
+    }
+
+    #[test]
+    fn parses_seconds_minutes_and_hours() {
+        assert_eq!(parse_time("42"), Some(42));
+        assert_eq!(parse_time("1:05"), Some(65));
+        assert_eq!(parse_time(" 1:02:05 "), Some(3725));
+        assert_eq!(parse_time(&format_time(5000)), Some(5000));
+    }
+
+    #[test]
+    fn rejects_invalid_times() {
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("1:2:3:4"), None);
+        assert_eq!(parse_time("1:xx"), None);
+        assert_eq!(parse_time("-5"), None);
+    }
+
+    #[test]
+    fn parses_sizes_with_units() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("4K"), Some(4096));
+        assert_eq!(parse_size("700 Mo"), Some(700 << 20));
+        assert_eq!(parse_size("1,5G"), Some(3 << 29));
+        assert_eq!(parse_size("1.5 GB"), Some(3 << 29));
+        assert_eq!(parse_size("2to"), Some(2 << 40));
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("G"), None);
+        assert_eq!(parse_size("-1G"), None);
+        assert_eq!(parse_size("beaucoup"), None);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 o");
+        assert_eq!(format_size(1536), "1.5 Ko");
+        assert_eq!(format_size(3 << 29), "1.5 Go");
+    }
+}
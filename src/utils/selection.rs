@@ -0,0 +1,161 @@
+use crate::error::{Error, Result};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TrackSelection {
+    ranges: Vec<(u32, Option<u32>)>,
+}
+
+impl TrackSelection {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() || spec == "*" || spec.eq_ignore_ascii_case("all") {
+            return Ok(Self::all());
+        }
+
+        let invalid = || Error::InvalidArgument(format!("sélection de pistes invalide : '{}'", spec));
+        let parse_number = |s: &str| s.trim().parse::<u32>().ok().filter(|&n| n > 0);
+
+        let mut ranges = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let range = match part.split_once('-') {
+                Some((start, "")) => (parse_number(start).ok_or_else(invalid)?, None),
+                Some(("", end)) => (1, Some(parse_number(end).ok_or_else(invalid)?)),
+                Some((start, end)) => {
+                    let start = parse_number(start).ok_or_else(invalid)?;
+                    let end = parse_number(end).ok_or_else(invalid)?;
+                    if end < start {
+                        return Err(invalid());
+                    }
+                    (start, Some(end))
+                }
+                None => {
+                    let n = parse_number(part).ok_or_else(invalid)?;
+                    (n, Some(n))
+                }
+            };
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn from_tracks(tracks: &[u32]) -> Option<Self> {
+        if tracks.is_empty() {
+            return None;
+        }
+
+        let mut sorted = tracks.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        let mut ranges: Vec<(u32, Option<u32>)> = Vec::new();
+        for n in sorted {
+            match ranges.last_mut() {
+                Some((_, Some(end))) if *end + 1 == n => *end = n,
+                _ => ranges.push((n, Some(n))),
+            }
+        }
+        Some(Self { ranges })
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, track: u32) -> bool {
+        self.is_all()
+            || self.ranges.iter().any(|&(start, end)| track >= start && end.is_none_or(|end| track <= end))
+    }
+
+    pub fn tracks(&self, first: u32, last: u32) -> Vec<u32> {
+        (first..=last).filter(|&n| self.contains(n)).collect()
+    }
+}
+
+impl fmt::Display for TrackSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_all() {
+            return write!(f, "all");
+        }
+
+        let parts: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|&(start, end)| match end {
+                Some(end) if end == start => start.to_string(),
+                Some(end) => format!("{}-{}", start, end),
+                None => format!("{}-", start),
+            })
+            .collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackSelection;
+
+    fn parse(spec: &str) -> TrackSelection {
+        TrackSelection::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn parses_single_tracks_and_ranges() {
+        let selection = parse("1, 3-5,8");
+        assert_eq!(selection.tracks(1, 10), vec![1, 3, 4, 5, 8]);
+        assert_eq!(selection.to_string(), "1,3-5,8");
+    }
+
+    #[test]
+    fn open_ranges_extend_to_the_album_bounds() {
+        let from = parse("10-");
+        assert!(!from.contains(9));
+        assert!(from.contains(10));
+        assert!(from.contains(99));
+        assert_eq!(from.to_string(), "10-");
+
+        let until = parse("-5");
+        assert_eq!(until.tracks(1, 12), vec![1, 2, 3, 4, 5]);
+        assert_eq!(until.to_string(), "1-5");
+    }
+
+    #[test]
+    fn wildcard_and_empty_select_everything() {
+        for spec in ["*", "all", "ALL", "", "  "] {
+            let selection = parse(spec);
+            assert!(selection.is_all());
+            assert!(selection.contains(1));
+            assert!(selection.contains(42));
+            assert_eq!(selection.to_string(), "all");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_specs() {
+        for spec in ["0", "5-3", "a", "1-b", "-", ",", "1--3"] {
+            assert!(TrackSelection::parse(spec).is_err(), "'{}' devrait être refusé", spec);
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for spec in ["2", "1-3,7", "4-", "1,3,5-9,12-"] {
+            assert_eq!(parse(&parse(spec).to_string()), parse(spec));
+        }
+    }
+
+    #[test]
+    fn from_tracks_merges_consecutive_numbers() {
+        let selection = TrackSelection::from_tracks(&[7, 2, 3, 4, 9, 3]).unwrap();
+        assert_eq!(selection.to_string(), "2-4,7,9");
+        assert_eq!(TrackSelection::from_tracks(&[]), None);
+    }
+}